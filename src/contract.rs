@@ -1,27 +1,49 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Order as CwOrder, Response, StdResult, Uint128, Decimal, Storage, StdError, Addr, Timestamp, OverflowError, DivideByZeroError
+    Order as CwOrder, Response, StdResult, Uint128, Uint256, Int128, Decimal, Storage, StdError, Addr, Timestamp, OverflowError, DivideByZeroError
 }; // Removed Rounding, Added DivideByZeroError
-use cw2::set_contract_version;
-use cw_storage_plus::Bound;
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::{Bound, Map, MultiIndex};
+use std::collections::BTreeMap;
 
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, QueryMsg, Outcome, OrderType, EventStatus, OrderStatus,
-    ConfigResponse, EventResponse, EventsResponse, OrderResponse, OrdersResponse,
-    MatchedBetsResponse
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, OutcomeIndex, OrderType, EventStatus, OrderStatus,
+    ConfigResponse, FeesResponse, EventResponse, EventsResponse, OrderResponse, OrdersResponse,
+    MatchedBetsResponse, UnclaimedAmount, UnclaimedResponse, OracleVotesResponse,
+    ResolverQueryMsg, ResolverOutcomeResponse, PriceFeedQueryMsg, PriceAtResponse, PriceCondition, PriceComparator,
+    OUTCOME_YES, OUTCOME_NO, OperatorScope, OperatorResponse, DepositResponse, NewOrder, TimeInForce,
+    OrderBookResponse, PriceLevel, BetRole, UserPositionResponse, RiskLimits, OddsBand, OddsLadderResponse, EscrowInvariantResponse,
 };
 use crate::state::{
     Config, Event, Order, MatchedBet, CONFIG, NEXT_EVENT_ID, NEXT_ORDER_ID, NEXT_BET_ID,
-    EVENTS, orders, MATCHED_BETS, EVENT_TO_MATCHED_BETS
+    EVENTS, orders, matched_bets, EVENT_TO_MATCHED_BETS, MARKET_STATS, v0_1, ORDER_BOOK, book_key, order_type_key,
+    Position, positions, default_outcomes, default_odds_ladder, ACCRUED_FEES, TOTAL_ESCROW, TOTAL_DEPOSITS, TOTAL_BONDS, PENDING_ADMIN, ResolutionProposal, OracleVote, OperatorGrant, OPERATORS, DEPOSITS
 };
 
 const CONTRACT_NAME: &str = "crates.io:injective-betting";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const MAX_OUTCOMES: usize = 64;
+const MAX_ORACLES: usize = 16;
+// Orders placed or cancelled by one batch message.
+const MAX_BATCH_ORDERS: usize = 50;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+const MAX_COMMISSION_BPS: u16 = 2_000;
+
+// How long a proposed resolution stays open to disputes unless configured otherwise.
+const DEFAULT_CHALLENGE_PERIOD_SECS: u64 = 86_400;
+// A dispute always costs something, so proposals cannot be held up for free.
+const DEFAULT_DISPUTE_BOND: u128 = 1_000_000;
+
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
 
+// Positions plus leftover orders processed per SettleEvent call.
+const DEFAULT_SETTLE_LIMIT: u32 = 50;
+const MAX_SETTLE_LIMIT: u32 = 200;
+
 // Helper function for Decimal * Uint128 -> Uint128 (with truncation)
 fn decimal_times_uint128_trunc(decimal_val: Decimal, uint128_val: Uint128) -> Result<Uint128, ContractError> {
     if decimal_val < Decimal::zero() {
@@ -62,11 +84,32 @@ pub fn instantiate(
         return Err(ContractError::InvalidDenom { expected_denom: "any non-empty string".to_string(), received_denom: msg.betting_denom });
     }
 
+    let commission_bps = msg.commission_bps.unwrap_or(0);
+    validate_commission(commission_bps)?;
+    let fee_collector = msg.fee_collector.map_or(Ok(admin.clone()), |addr_str| deps.api.addr_validate(&addr_str))?;
+    let arbitrator = msg.arbitrator.map(|addr_str| deps.api.addr_validate(&addr_str)).transpose()?;
+    let price_oracle = msg.price_oracle.map(|addr_str| deps.api.addr_validate(&addr_str)).transpose()?;
+    let dispute_bond = msg.dispute_bond.unwrap_or(Uint128::new(DEFAULT_DISPUTE_BOND));
+    validate_dispute_bond(dispute_bond)?;
+
     let config = Config {
         admin,
         betting_denom: msg.betting_denom,
+        commission_bps,
+        fee_collector,
+        paused: false,
+        challenge_period_secs: msg.challenge_period_secs.unwrap_or(DEFAULT_CHALLENGE_PERIOD_SECS),
+        dispute_bond,
+        arbitrator,
+        price_oracle,
+        limits: RiskLimits::default(),
+        odds_ladder: default_odds_ladder(),
     };
     CONFIG.save(deps.storage, &config)?;
+    ACCRUED_FEES.save(deps.storage, &Uint128::zero())?;
+    TOTAL_ESCROW.save(deps.storage, &Uint128::zero())?;
+    TOTAL_DEPOSITS.save(deps.storage, &Uint128::zero())?;
+    TOTAL_BONDS.save(deps.storage, &Uint128::zero())?;
 
     NEXT_EVENT_ID.save(deps.storage, &0u64)?;
     NEXT_ORDER_ID.save(deps.storage, &0u64)?;
@@ -75,7 +118,78 @@ pub fn instantiate(
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("admin", config.admin.to_string())
-        .add_attribute("betting_denom", config.betting_denom))
+        .add_attribute("betting_denom", config.betting_denom)
+        .add_attribute("commission_bps", config.commission_bps.to_string())
+        .add_attribute("fee_collector", config.fee_collector.to_string()))
+}
+
+fn validate_dispute_bond(dispute_bond: Uint128) -> Result<(), ContractError> {
+    if dispute_bond.is_zero() {
+        return Err(ContractError::InvalidDisputeBond {});
+    }
+    Ok(())
+}
+
+fn single_coin_sent(info: &MessageInfo, betting_denom: &str) -> Result<Coin, ContractError> {
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFundsSent {});
+    }
+    if info.funds.len() > 1 {
+        return Err(ContractError::MultipleCoinsSent {});
+    }
+    let sent_coin = info.funds[0].clone();
+    if sent_coin.denom != betting_denom {
+        return Err(ContractError::InvalidDenom { expected_denom: betting_denom.to_string(), received_denom: sent_coin.denom });
+    }
+    Ok(sent_coin)
+}
+
+/// A price market is a plain Yes/No event answered by the configured price oracle, so it takes no
+/// custom outcomes or resolver, and betting must close by the time the price is observed.
+fn validate_price_condition(
+    deps: Deps,
+    env: &Env,
+    condition: &PriceCondition,
+    outcomes: &Option<Vec<String>>,
+    resolver_market_id: &Option<String>,
+    betting_closes_at: Option<Timestamp>,
+    resolve_by: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    let invalid = |reason: &str| Err(ContractError::InvalidPriceCondition { reason: reason.to_string() });
+    if CONFIG.load(deps.storage)?.price_oracle.is_none() {
+        return Err(ContractError::NoPriceOracle {});
+    }
+    if condition.price_feed_id.is_empty() {
+        return invalid("price_feed_id cannot be empty");
+    }
+    if condition.observation_time <= env.block.time {
+        return invalid("observation_time must be in the future");
+    }
+    if outcomes.is_some() || resolver_market_id.is_some() {
+        return invalid("price markets have fixed Yes/No outcomes and no resolver contract");
+    }
+    if betting_closes_at.is_some_and(|closes_at| closes_at > condition.observation_time)
+        || resolve_by.is_some_and(|resolve_by| resolve_by <= condition.observation_time)
+    {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    Ok(())
+}
+
+fn price_condition_met(condition: &PriceCondition, price: Decimal) -> bool {
+    match condition.comparator {
+        PriceComparator::Above => price > condition.threshold,
+        PriceComparator::AtOrAbove => price >= condition.threshold,
+        PriceComparator::Below => price < condition.threshold,
+        PriceComparator::AtOrBelow => price <= condition.threshold,
+    }
+}
+
+fn validate_commission(commission_bps: u16) -> Result<(), ContractError> {
+    if commission_bps > MAX_COMMISSION_BPS {
+        return Err(ContractError::InvalidCommission { commission_bps, max_bps: MAX_COMMISSION_BPS });
+    }
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -86,434 +200,1976 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreateEvent { description, oracle_addr, resolution_deadline } => 
-            execute_create_event(deps, env, info, description, oracle_addr, resolution_deadline),
-        ExecuteMsg::PlaceOrder { event_id, order_type, outcome, stake, odds } => 
-            execute_place_order(deps, env, info, event_id, order_type, outcome, stake, odds),
+        ExecuteMsg::CreateEvent { description, oracle_addr, oracles, oracle_threshold, resolver_market_id, price_condition, limits, betting_closes_at, resolve_by, outcomes } => 
+            execute_create_event(deps, env, info, description, oracle_addr, oracles, oracle_threshold, resolver_market_id, price_condition, limits, betting_closes_at, resolve_by, outcomes),
+        ExecuteMsg::PlaceOrder { event_id, order_type, outcome, stake, odds, time_in_force, owner } => 
+            execute_place_order(deps, env, info, event_id, order_type, outcome, stake, odds, time_in_force, owner),
+        ExecuteMsg::PlaceMarketOrder { event_id, order_type, outcome, stake, worst_odds, owner } => 
+            execute_place_market_order(deps, env, info, event_id, order_type, outcome, stake, worst_odds, owner),
+        ExecuteMsg::PlaceOrders { orders, owner } => 
+            execute_place_orders(deps, env, info, orders, owner),
         ExecuteMsg::CancelOrder { order_id } => 
             execute_cancel_order(deps, env, info, order_id),
-        ExecuteMsg::ResolveEvent { event_id, winning_outcome } => 
-            execute_resolve_event(deps, env, info, event_id, winning_outcome),
+        ExecuteMsg::AmendOrder { order_id, new_odds, new_remaining_stake } => 
+            execute_amend_order(deps, env, info, order_id, new_odds, new_remaining_stake),
+        ExecuteMsg::CancelOrders { order_ids } => 
+            execute_cancel_orders(deps, env, info, order_ids),
+        ExecuteMsg::CancelAllOrders { event_id, owner } => 
+            execute_cancel_all_orders(deps, env, info, event_id, owner),
+        ExecuteMsg::CancelExpiredOrders { order_ids } => 
+            execute_cancel_expired_orders(deps, env, order_ids),
+        ExecuteMsg::ProposeResolution { event_id, winning_outcome } => 
+            execute_propose_resolution(deps, env, info, event_id, winning_outcome),
+        ExecuteMsg::ResolveFromOracle { event_id } =>
+            execute_resolve_from_oracle(deps, env, info, event_id),
+        ExecuteMsg::DisputeResolution { event_id } =>
+            execute_dispute_resolution(deps, env, info, event_id),
+        ExecuteMsg::FinalizeResolution { event_id } =>
+            execute_finalize_resolution(deps, env, info, event_id),
+        ExecuteMsg::RuleOnDispute { event_id, winning_outcome } =>
+            execute_rule_on_dispute(deps, env, info, event_id, winning_outcome),
+        ExecuteMsg::CancelEvent { event_id } =>
+            execute_cancel_event(deps, env, info, event_id),
+        ExecuteMsg::ClaimWinnings { event_id } =>
+            execute_claim_winnings(deps, env, info, event_id),
+        ExecuteMsg::SettleEvent { event_id, limit } =>
+            execute_settle_event(deps, env, info, event_id, limit),
+        ExecuteMsg::RefundUnmatched { event_id, limit } =>
+            execute_refund_unmatched(deps, env, info, event_id, limit),
+        ExecuteMsg::VoidExpiredEvent { event_id } =>
+            execute_void_expired_event(deps, env, info, event_id),
+        ExecuteMsg::UpdateConfig { commission_bps, fee_collector, challenge_period_secs, dispute_bond, arbitrator, price_oracle, limits, odds_ladder } =>
+            execute_update_config(deps, env, info, commission_bps, fee_collector, challenge_period_secs, dispute_bond, arbitrator, price_oracle, limits, odds_ladder),
+        ExecuteMsg::WithdrawFees {} =>
+            execute_withdraw_fees(deps, env, info),
+        ExecuteMsg::ProposeNewAdmin { new_admin } =>
+            execute_propose_new_admin(deps, env, info, new_admin),
+        ExecuteMsg::AcceptAdmin {} =>
+            execute_accept_admin(deps, env, info),
+        ExecuteMsg::GrantOperator { operator, scope, expires } =>
+            execute_grant_operator(deps, env, info, operator, scope, expires),
+        ExecuteMsg::RevokeOperator { operator } =>
+            execute_revoke_operator(deps, env, info, operator),
+        ExecuteMsg::Deposit {} =>
+            execute_deposit(deps, env, info),
+        ExecuteMsg::WithdrawDeposit { amount } =>
+            execute_withdraw_deposit(deps, env, info, amount),
+        ExecuteMsg::Pause {} =>
+            execute_set_paused(deps, env, info, true),
+        ExecuteMsg::Unpause {} =>
+            execute_set_paused(deps, env, info, false),
+        ExecuteMsg::SuspendEvent { event_id } =>
+            execute_set_event_suspended(deps, env, info, event_id, true),
+        ExecuteMsg::ResumeEvent { event_id } =>
+            execute_set_event_suspended(deps, env, info, event_id, false),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_create_event(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     description: String,
     oracle_addr: Option<String>,
-    resolution_deadline: Option<Timestamp>,
+    oracles: Option<Vec<String>>,
+    oracle_threshold: Option<u32>,
+    resolver_market_id: Option<String>,
+    price_condition: Option<PriceCondition>,
+    limits: Option<RiskLimits>,
+    betting_closes_at: Option<Timestamp>,
+    resolve_by: Option<Timestamp>,
+    outcomes: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
     if description.is_empty() {
         return Err(ContractError::InvalidDescription {});
     }
+    let config = CONFIG.load(deps.storage)?;
+    let limits = limits.unwrap_or_default();
+    validate_limits(&effective_limits(&config.limits, &limits))?;
+    // Only the admin may loosen the global limits for an event.
+    if info.sender != config.admin && !tightens_limits(&config.limits, &limits) {
+        return Err(ContractError::InvalidLimits { reason: "only the admin can relax the global limits".to_string() });
+    }
+    if let Some(condition) = &price_condition {
+        validate_price_condition(deps.as_ref(), &env, condition, &outcomes, &resolver_market_id, betting_closes_at, resolve_by)?;
+    }
+    // Betting never stays open past the price observation or the point where the event may be voided.
+    let betting_closes_at = betting_closes_at
+        .or(price_condition.as_ref().map(|condition| condition.observation_time))
+        .or(resolve_by);
+    if betting_closes_at.is_some_and(|closes_at| closes_at <= env.block.time) {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    if let (Some(closes_at), Some(resolve_by)) = (betting_closes_at, resolve_by) {
+        if resolve_by < closes_at {
+            return Err(ContractError::InvalidSchedule {});
+        }
+    }
+    let outcomes = outcomes.unwrap_or_else(default_outcomes);
+    if outcomes.len() < 2 || outcomes.len() > MAX_OUTCOMES {
+        return Err(ContractError::InvalidOutcomeCount { count: outcomes.len() });
+    }
+    for (idx, name) in outcomes.iter().enumerate() {
+        if name.trim().is_empty() || outcomes[..idx].contains(name) {
+            return Err(ContractError::InvalidOutcomeName { name: name.clone() });
+        }
+    }
 
-    let oracle = match oracle_addr {
-        Some(addr_str) => deps.api.addr_validate(&addr_str)?,
-        None => info.sender.clone(),
+    let oracles = match (oracle_addr, oracles) {
+        (Some(_), Some(_)) => return Err(ContractError::InvalidOracleSet { reason: "set either oracle_addr or oracles".to_string() }),
+        (Some(addr_str), None) => vec![deps.api.addr_validate(&addr_str)?],
+        (None, Some(addr_strs)) => addr_strs.iter().map(|addr_str| deps.api.addr_validate(addr_str)).collect::<StdResult<_>>()?,
+        (None, None) => vec![info.sender.clone()],
     };
+    if oracles.is_empty() || oracles.len() > MAX_ORACLES {
+        return Err(ContractError::InvalidOracleSet { reason: format!("between 1 and {} oracles are required", MAX_ORACLES) });
+    }
+    if oracles.iter().enumerate().any(|(idx, oracle)| oracles[..idx].contains(oracle)) {
+        return Err(ContractError::InvalidOracleSet { reason: "duplicate oracle".to_string() });
+    }
+    // Defaults to a simple majority of the panel.
+    let oracle_threshold = oracle_threshold.unwrap_or(oracles.len() as u32 / 2 + 1);
+    if oracle_threshold == 0 || oracle_threshold as usize > oracles.len() {
+        return Err(ContractError::InvalidOracleSet { reason: format!("threshold must be between 1 and {}", oracles.len()) });
+    }
+    if resolver_market_id.is_some() {
+        let is_single_contract = oracles.len() == 1 && deps.querier.query_wasm_contract_info(oracles[0].clone()).is_ok();
+        if !is_single_contract {
+            return Err(ContractError::InvalidOracleSet { reason: "a resolver market needs a single oracle contract".to_string() });
+        }
+    }
     let event_id = NEXT_EVENT_ID.update(deps.storage, |id| -> StdResult<_> { Ok(id + 1) })?;
     
     let event = Event {
         id: event_id,
         creator: info.sender.clone(),
         description,
-        oracle,
+        oracle: oracles[0].clone(),
         status: EventStatus::Open,
         winning_outcome: None,
-        resolution_deadline,
+        betting_closes_at,
+        resolve_by,
         creation_time: env.block.time,
+        commission_bps: config.commission_bps,
+        outcomes,
+        suspended: false,
+        settlement_cursor: None,
+        proposal: None,
+        oracles,
+        oracle_threshold,
+        oracle_votes: Vec::new(),
+        resolver_market_id,
+        price_condition,
+        limits,
     };
     EVENTS.save(deps.storage, event_id, &event)?;
-    EVENT_TO_MATCHED_BETS.save(deps.storage, event_id, &Vec::new())?;
 
     Ok(Response::new()
         .add_attribute("method", "create_event")
         .add_attribute("event_id", event_id.to_string())
         .add_attribute("creator", info.sender.to_string())
-        .add_attribute("oracle", event.oracle.to_string()))
+        .add_attribute("oracle", event.oracle.to_string())
+        .add_attribute("oracle_threshold", format!("{}/{}", event.oracle_threshold, event.oracles.len()))
+        .add_attribute("outcome_count", event.outcomes.len().to_string()))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_place_order(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     event_id: u64,
     order_type: OrderType,
-    outcome: Outcome,
+    outcome: OutcomeIndex,
     backer_stake_amount_msg: Uint128,
     odds: Decimal,
+    time_in_force: Option<TimeInForce>,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    let owner = acting_owner(deps.as_ref(), &env, &info.sender, owner, OperatorScope::Place)?;
+    let new_order = NewOrder { event_id, order_type, outcome, stake: backer_stake_amount_msg, odds, time_in_force };
+    let placed = open_order(deps.storage, &env, &config, &owner, &new_order)?;
+    fund_orders(deps.storage, &info, &config, &owner, placed.escrow)?;
 
-    if backer_stake_amount_msg.is_zero() {
-        return Err(ContractError::InvalidStakeAmount {});
+    let mut res = Response::new()
+        .add_messages(refund_messages(&config, vec![(owner.clone(), placed.matches.price_improvement_refund + placed.unfilled_refund)]));
+    if !placed.matches.price_improvement_refund.is_zero() {
+        res = res.add_attribute("price_improvement_refund", placed.matches.price_improvement_refund.to_string());
     }
-    if odds <= Decimal::one() {
-        return Err(ContractError::InvalidOdds {});
+    if !placed.unfilled_refund.is_zero() {
+        res = res.add_attribute("unfilled_refund", placed.unfilled_refund.to_string());
     }
+    res = res
+        .add_attribute("method", "place_order")
+        .add_attribute("order_id", placed.order_id.to_string())
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("order_type", format!("{:?}", order_type))
+        .add_attribute("outcome", format!("{:?}", outcome))
+        .add_attribute("backer_stake", backer_stake_amount_msg.to_string())
+        .add_attribute("odds", odds.to_string());
 
-    let event = EVENTS.load(deps.storage, event_id)
-        .map_err(|_| ContractError::EventNotFound { event_id })?;
-    if event.status != EventStatus::Open {
-        return Err(ContractError::EventNotOpen { event_id });
+    if owner != info.sender {
+        res = res.add_attribute("operator", info.sender.to_string());
     }
-    if let Some(deadline) = event.resolution_deadline {
-        if env.block.time > deadline {
-            return Err(ContractError::DeadlinePassed {});
-        }
+    Ok(add_match_attributes(res, &placed.matches))
+}
+
+/// Sweeps the opposite side of the book from the best odds down to `worst_odds`, creating one
+/// matched bet per resting order hit. The order never rests: whatever is left is refunded, along
+/// with any price improvement. Funds are sent as for a limit order at `worst_odds`.
+#[allow(clippy::too_many_arguments)]
+fn execute_place_market_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: u64,
+    order_type: OrderType,
+    outcome: OutcomeIndex,
+    stake: Uint128,
+    worst_odds: Decimal,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let owner = acting_owner(deps.as_ref(), &env, &info.sender, owner, OperatorScope::Place)?;
+    let new_order = NewOrder { event_id, order_type, outcome, stake, odds: worst_odds, time_in_force: Some(TimeInForce::ImmediateOrCancel) };
+    let placed = open_order(deps.storage, &env, &config, &owner, &new_order)?;
+    if placed.matches.newly_matched_bet_ids.is_empty() {
+        return Err(ContractError::NoLiquidityWithinOdds { event_id });
     }
+    fund_orders(deps.storage, &info, &config, &owner, placed.escrow)?;
+
+    let mut matched_stake = Uint128::zero();
+    let mut weighted_odds = Uint256::zero();
+    for bet_id in &placed.matches.newly_matched_bet_ids {
+        let bet = matched_bets().load(deps.storage, *bet_id)?;
+        matched_stake += bet.backer_stake.amount;
+        weighted_odds += Uint256::from(bet.odds.atomics()) * Uint256::from(bet.backer_stake.amount);
+    }
+    let average_atomics = Uint128::try_from(weighted_odds / Uint256::from(matched_stake))
+        .map_err(|e| ContractError::CalculationError { msg: format!("Average odds overflow: {}", e) })?;
+    let average_odds = Decimal::from_atomics(average_atomics, Decimal::DECIMAL_PLACES)
+        .map_err(|e| ContractError::CalculationError { msg: format!("Average odds error: {}", e) })?;
 
-    if info.funds.is_empty() {
-        return Err(ContractError::NoFundsSent {});
+    let refund = placed.matches.price_improvement_refund + placed.unfilled_refund;
+    let mut res = Response::new()
+        .add_messages(refund_messages(&config, vec![(owner.clone(), refund)]))
+        .add_attribute("method", "place_market_order")
+        .add_attribute("order_id", placed.order_id.to_string())
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("order_type", format!("{:?}", order_type))
+        .add_attribute("outcome", format!("{:?}", outcome))
+        .add_attribute("worst_odds", worst_odds.to_string())
+        .add_attribute("matched_stake", matched_stake.to_string())
+        .add_attribute("average_odds", average_odds.to_string())
+        .add_attribute("refund", refund.to_string());
+    if owner != info.sender {
+        res = res.add_attribute("operator", info.sender.to_string());
     }
-    if info.funds.len() > 1 {
-        return Err(ContractError::MultipleCoinsSent {});
+    Ok(add_match_attributes(res, &placed.matches))
+}
+
+/// Places several orders for one owner, funded by a single payment (or deposit debit) covering the
+/// sum of their back stakes and lay liabilities. Price improvement and unfilled immediate-or-cancel
+/// remainders are refunded in one transfer.
+fn execute_place_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_orders: Vec<NewOrder>,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    if new_orders.is_empty() || new_orders.len() > MAX_BATCH_ORDERS {
+        return Err(ContractError::InvalidBatchSize { size: new_orders.len(), max: MAX_BATCH_ORDERS });
     }
-    let sent_coin = info.funds[0].clone();
-    if sent_coin.denom != config.betting_denom {
-        return Err(ContractError::InvalidDenom { expected_denom: config.betting_denom.clone(), received_denom: sent_coin.denom });
+    let config = CONFIG.load(deps.storage)?;
+    let owner = acting_owner(deps.as_ref(), &env, &info.sender, owner, OperatorScope::Place)?;
+
+    let mut total_escrow = Uint128::zero();
+    let mut total_refund = Uint128::zero();
+    let mut res = Response::new()
+        .add_attribute("method", "place_orders")
+        .add_attribute("owner", owner.to_string());
+    for new_order in &new_orders {
+        let placed = open_order(deps.storage, &env, &config, &owner, new_order)?;
+        total_escrow = total_escrow.checked_add(placed.escrow)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Batch escrow overflow: {}", e) })?;
+        total_refund += placed.matches.price_improvement_refund + placed.unfilled_refund;
+        res = add_match_attributes(res.add_attribute("order_id", placed.order_id.to_string()), &placed.matches);
     }
+    fund_orders(deps.storage, &info, &config, &owner, total_escrow)?;
 
-    let required_deposit: Uint128;
-    let order_backer_stake = Coin { denom: config.betting_denom.clone(), amount: backer_stake_amount_msg };
+    if owner != info.sender {
+        res = res.add_attribute("operator", info.sender.to_string());
+    }
+    if !total_refund.is_zero() {
+        res = res
+            .add_messages(refund_messages(&config, vec![(owner, total_refund)]))
+            .add_attribute("refund", total_refund.to_string());
+    }
+    Ok(res.add_attribute("total_escrow", total_escrow.to_string()))
+}
 
-    match order_type {
-        OrderType::Back => {
-            required_deposit = order_backer_stake.amount;
-        }
-        OrderType::Lay => {
-            let odds_factor = odds.checked_sub(Decimal::one())
-                .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Odds factor calculation error: {}", e) })?;
-            if odds_factor.is_zero() { 
-                 return Err(ContractError::InvalidOdds {});
-            }
-            required_deposit = decimal_times_uint128_trunc(odds_factor, order_backer_stake.amount)?;
-        }
+struct PlacedOrder {
+    order_id: u64,
+    // Back stake or lay liability the order required before any price improvement.
+    escrow: Uint128,
+    matches: MatchResult,
+    // Escrow of an immediate-or-cancel remainder that was cancelled instead of resting.
+    unfilled_refund: Uint128,
+}
+
+/// Validates, books and matches one order for `owner`, then applies its time in force. Paying for
+/// it is left to the caller, which may cover several orders at once.
+fn open_order(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    owner: &Addr,
+    new_order: &NewOrder,
+) -> Result<PlacedOrder, ContractError> {
+    let NewOrder { event_id, order_type, outcome, stake, odds, time_in_force } = new_order.clone();
+    let time_in_force = time_in_force.unwrap_or(TimeInForce::GoodTilCancelled);
+    if stake.is_zero() {
+        return Err(ContractError::InvalidStakeAmount {});
     }
+    if odds <= Decimal::one() {
+        return Err(ContractError::InvalidOdds {});
+    }
+    ensure_on_ladder(&config.odds_ladder, odds)?;
 
-    if sent_coin.amount != required_deposit {
-        return Err(ContractError::InsufficientFundsSent { 
-            required: required_deposit.to_string() + &config.betting_denom, 
-            sent: sent_coin.amount.to_string() + &sent_coin.denom 
-        });
+    let event = EVENTS.load(storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    ensure_trading_allowed(config, &event, env)?;
+    ensure_valid_outcome(&event, outcome)?;
+    let limits = effective_limits(&config.limits, &event.limits);
+    if let Some(min_stake) = limits.min_stake.filter(|min_stake| stake < *min_stake) {
+        return Err(ContractError::StakeBelowMinimum { stake: stake.to_string(), min: min_stake.to_string() });
     }
-    
-    let order_id = NEXT_ORDER_ID.update(deps.storage, |id| -> StdResult<_> { Ok(id + 1) })?;
+    if let Some(max_stake) = limits.max_stake.filter(|max_stake| stake > *max_stake) {
+        return Err(ContractError::StakeAboveMaximum { stake: stake.to_string(), max: max_stake.to_string() });
+    }
+    if let Some(max_volume) = limits.max_matched_volume {
+        if event_matched_volume(storage, event_id)? >= max_volume {
+            return Err(ContractError::EventVolumeLimitReached { event_id });
+        }
+    }
+
+    let order_backer_stake = Coin { denom: config.betting_denom.clone(), amount: stake };
+    let escrow = order_escrow(order_type, odds, stake)?;
+
+    let order_id = NEXT_ORDER_ID.update(storage, |id| -> StdResult<_> { Ok(id + 1) })?;
     let order = Order {
         id: order_id,
         event_id,
-        owner: info.sender.clone(),
+        owner: owner.clone(),
         order_type,
         outcome,
         initial_backer_stake: order_backer_stake.clone(),
-        remaining_backer_stake: order_backer_stake.clone(),
+        remaining_backer_stake: order_backer_stake,
         odds,
+        remaining_escrow: escrow,
         creation_time: env.block.time,
         status: OrderStatus::Open,
+        expires_at: None,
+    };
+    let order = match time_in_force {
+        TimeInForce::GoodTilDate { expires } if expires <= env.block.time => return Err(ContractError::InvalidExpiry {}),
+        TimeInForce::GoodTilDate { expires } => Order { expires_at: Some(expires), ..order },
+        TimeInForce::PostOnly if crosses_book(storage, env, &order)? => return Err(ContractError::PostOnlyWouldMatch {}),
+        _ => order,
     };
-    orders().save(deps.storage, order_id, &order)?;
+    orders().save(storage, order_id, &order)?;
+    ORDER_BOOK.save(storage, book_key(&order), &order.owner)?;
+    add_open_escrow(storage, &event, owner, escrow)?;
+    if let Some(max_liability) = limits.max_liability_per_user {
+        let exposure = position_exposure(&load_position(storage, event_id, owner)?)?;
+        if exposure > max_liability {
+            return Err(ContractError::LiabilityLimitExceeded { event_id, exposure: exposure.to_string(), limit: max_liability.to_string() });
+        }
+    }
 
-    let match_results = try_match_order(deps.storage, env.clone(), order_id)?;
-    
-    let mut res = Response::new()
-        .add_attribute("method", "place_order")
-        .add_attribute("order_id", order_id.to_string())
-        .add_attribute("event_id", event_id.to_string())
-        .add_attribute("owner", info.sender.to_string())
-        .add_attribute("order_type", format!("{:?}", order_type))
-        .add_attribute("outcome", format!("{:?}", outcome))
-        .add_attribute("backer_stake", order_backer_stake.amount.to_string())
-        .add_attribute("odds", odds.to_string());
+    let matches = try_match_order(storage, env.clone(), order_id)?;
+    let mut unfilled_refund = Uint128::zero();
+    if !matches.order_fully_filled {
+        match time_in_force {
+            TimeInForce::FillOrKill => return Err(ContractError::FillOrKillNotFilled {}),
+            TimeInForce::ImmediateOrCancel => {
+                let remainder = orders().load(storage, order_id)?;
+                unfilled_refund = release_cancelled_order(storage, remainder)?.1;
+            }
+            _ => {}
+        }
+    }
+    Ok(PlacedOrder { order_id, escrow, matches, unfilled_refund })
+}
+
+/// Orders an owner places itself are paid in full with the message; orders an operator places are
+/// funded from the owner's deposit, never by the operator.
+fn fund_orders(storage: &mut dyn Storage, info: &MessageInfo, config: &Config, owner: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    if *owner == info.sender {
+        let sent_coin = single_coin_sent(info, &config.betting_denom)?;
+        if sent_coin.amount != amount {
+            return Err(ContractError::InsufficientFundsSent { 
+                required: amount.to_string() + &config.betting_denom, 
+                sent: sent_coin.amount.to_string() + &sent_coin.denom 
+            });
+        }
+        Ok(())
+    } else {
+        if !info.funds.is_empty() {
+            return Err(ContractError::OperatorFundsNotAccepted {});
+        }
+        debit_deposit(storage, config, owner, amount)
+    }
+}
 
+fn add_match_attributes(mut res: Response, match_results: &MatchResult) -> Response {
     for matched_bet_id in &match_results.newly_matched_bet_ids { // Iterate by reference
         res = res.add_attribute("matched_bet_id", matched_bet_id.to_string());
     }
     if match_results.order_fully_filled {
-         res = res.add_attribute("order_status_after_match", "Filled");
+         res.add_attribute("order_status_after_match", "Filled")
     } else if !match_results.newly_matched_bet_ids.is_empty() { 
-         res = res.add_attribute("order_status_after_match", "PartiallyFilled");
+         res.add_attribute("order_status_after_match", "PartiallyFilled")
     } else {
-         res = res.add_attribute("order_status_after_match", "Open");
+         res.add_attribute("order_status_after_match", "Open")
     }
+}
 
-    Ok(res)
+/// The owner an order is placed for: the sender itself, or an owner that authorized the sender.
+fn acting_owner(deps: Deps, env: &Env, sender: &Addr, owner: Option<String>, scope: OperatorScope) -> Result<Addr, ContractError> {
+    let Some(owner) = owner else {
+        return Ok(sender.clone());
+    };
+    let owner = deps.api.addr_validate(&owner)?;
+    if owner != *sender {
+        ensure_operator(deps.storage, env, &owner, sender, scope)?;
+    }
+    Ok(owner)
+}
+
+fn ensure_operator(storage: &dyn Storage, env: &Env, owner: &Addr, operator: &Addr, scope: OperatorScope) -> Result<(), ContractError> {
+    let grant = OPERATORS.may_load(storage, (owner, operator))?
+        .ok_or(ContractError::Unauthorized {})?;
+    let in_scope = grant.scope == scope || grant.scope == OperatorScope::Both;
+    let expired = grant.expires.is_some_and(|expires| env.block.time >= expires);
+    if !in_scope || expired {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn debit_deposit(storage: &mut dyn Storage, config: &Config, owner: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let available = DEPOSITS.may_load(storage, owner)?.unwrap_or_default();
+    let remaining = available.checked_sub(amount).map_err(|_| ContractError::InsufficientDeposit {
+        required: amount.to_string() + &config.betting_denom,
+        available: available.to_string() + &config.betting_denom,
+    })?;
+    DEPOSITS.save(storage, owner, &remaining)?;
+    TOTAL_DEPOSITS.update(storage, |total| -> Result<_, ContractError> {
+        total.checked_sub(amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total deposits underflow: {}", e) })
+    })?;
+    Ok(())
 }
 
 struct MatchResult {
     newly_matched_bet_ids: Vec<u64>,
     order_fully_filled: bool,
+    // Liability released when an incoming Lay is filled at a lower (better) price than it escrowed for.
+    price_improvement_refund: Uint128,
 }
 
-fn try_match_order(
-    storage: &mut dyn Storage,
-    env: Env,
-    new_order_id: u64,
-) -> Result<MatchResult, ContractError> {
-    let mut newly_matched_bet_ids = Vec::new();
-    let mut new_order = orders().load(storage, new_order_id)?;
-    let config = CONFIG.load(storage)?;
+/// Whether a resting counter-order at `resting_odds` is an acceptable price for the incoming order.
+/// A backer takes any lay offering at least its odds; a layer takes any back asking at most its odds.
+fn is_price_acceptable(incoming: &Order, resting_odds: Decimal) -> bool {
+    match incoming.order_type {
+        OrderType::Back => resting_odds >= incoming.odds,
+        OrderType::Lay => resting_odds <= incoming.odds,
+    }
+}
 
-    if new_order.status == OrderStatus::Filled || new_order.remaining_backer_stake.amount.is_zero() { 
-        return Ok(MatchResult { newly_matched_bet_ids, order_fully_filled: true });
+fn lay_liability_for(odds: Decimal, backer_stake: Uint128) -> Result<Uint128, ContractError> {
+    let odds_factor = odds.checked_sub(Decimal::one())
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Liability odds factor error: {}", e) })?;
+    decimal_times_uint128_trunc(odds_factor, backer_stake)
+}
+
+/// Funds an order needs to hold for `backer_stake` of unmatched size.
+fn order_escrow(order_type: OrderType, odds: Decimal, backer_stake: Uint128) -> Result<Uint128, ContractError> {
+    match order_type {
+        OrderType::Back => Ok(backer_stake),
+        OrderType::Lay => lay_liability_for(odds, backer_stake),
     }
+}
 
-    let counter_order_type = match new_order.order_type {
+/// Takes the escrow freed by a fill that just reduced the order's remaining stake: everything above
+/// what the rest of the order needs. Liability is truncated on the remainder rather than on each
+/// slice, so the fills and the final refund always add up to the deposit.
+fn take_filled_escrow(order: &mut Order) -> Result<Uint128, ContractError> {
+    let still_needed = order_escrow(order.order_type, order.odds, order.remaining_backer_stake.amount)?;
+    let filled = order.remaining_escrow.checked_sub(still_needed)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Order {} escrow underflow: {}", order.id, e) })?;
+    order.remaining_escrow = still_needed;
+    Ok(filled)
+}
+
+/// Good-til-date orders stop matching once their expiry is reached.
+fn order_expired(order: &Order, env: &Env) -> bool {
+    order.expires_at.is_some_and(|expires| env.block.time >= expires)
+}
+
+/// Whether `order` would match a resting counter-order straight away.
+fn crosses_book(storage: &dyn Storage, env: &Env, order: &Order) -> Result<bool, ContractError> {
+    let counter_order_type = match order.order_type {
         OrderType::Back => OrderType::Lay,
         OrderType::Lay => OrderType::Back,
     };
-    let outcome_u8 = match new_order.outcome { Outcome::Yes => 0, Outcome::No => 1 };
-    
-    // Prefix for sub_prefix is event_id (first part of the (u64, u8) index key)
-    let sub_prefix_key_for_index = new_order.event_id; 
-    
-    let mut potential_matches_data: Vec<(u64, Order)> = Vec::new();
-    for item_result in orders()
-        .idx
-        .event_outcome_params 
-        .sub_prefix(sub_prefix_key_for_index) 
-        .range(storage, None, None, CwOrder::Ascending) 
-    {
-        let (order_primary_key_u64, order_from_iterator) = item_result?; 
-        
-        let current_order_outcome_u8 = match order_from_iterator.outcome { Outcome::Yes => 0, Outcome::No => 1 };
-
-        // Manual filtering for outcome (second part of index key), type, and odds
-        if current_order_outcome_u8 == outcome_u8 && 
-           order_from_iterator.order_type == counter_order_type &&
-           order_from_iterator.odds == new_order.odds {
-            
-            if order_primary_key_u64 != new_order.id && 
-               (order_from_iterator.status == OrderStatus::Open || order_from_iterator.status == OrderStatus::PartiallyFilled) &&
-               order_from_iterator.owner != new_order.owner { 
-                 potential_matches_data.push((order_primary_key_u64, order_from_iterator));
-            }
+    let counter_side = (order.event_id, order.outcome, order_type_key(counter_order_type));
+    for entry in ORDER_BOOK.prefix(counter_side).range(storage, None, None, CwOrder::Ascending) {
+        let ((_, resting_order_id), resting_owner) = entry?;
+        if resting_owner == order.owner {
+            continue;
+        }
+        let resting_order = orders().load(storage, resting_order_id)?;
+        if !is_price_acceptable(order, resting_order.odds) {
+            return Ok(false);
+        }
+        if !order_expired(&resting_order, env) {
+            return Ok(true);
         }
     }
+    Ok(false)
+}
 
-    let mut matched_any_this_call = false;
+// Positions are opened by `add_open_escrow` when an order is placed, so every other update finds one.
+fn load_position(storage: &dyn Storage, event_id: u64, owner: &Addr) -> StdResult<Position> {
+    positions().load(storage, (event_id, owner.clone()))
+}
 
-    for (_existing_order_id, mut existing_order) in potential_matches_data { 
-        if new_order.remaining_backer_stake.amount.is_zero() { break; } 
-        
-        let matchable_backer_stake_amount = new_order.remaining_backer_stake.amount.min(existing_order.remaining_backer_stake.amount);
+fn add_open_escrow(storage: &mut dyn Storage, event: &Event, owner: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let event_id = event.id;
+    let mut position = positions()
+        .may_load(storage, (event_id, owner.clone()))?
+        .unwrap_or_else(|| Position::new(event_id, owner.clone(), event.outcomes.len()));
+    position.open_escrow = position.open_escrow.checked_add(amount)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Open escrow overflow: {}", e) })?;
+    positions().save(storage, (event_id, owner.clone()), &position)?;
+    lock_escrow(storage, amount)
+}
 
-        if matchable_backer_stake_amount.is_zero() {
-            continue;
-        }
-        matched_any_this_call = true;
+/// Returns open escrow to its owner, so it leaves the contract's total escrow too.
+fn release_open_escrow(storage: &mut dyn Storage, event_id: u64, owner: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    fill_open_escrow(storage, event_id, owner, amount)?;
+    unlock_escrow(storage, amount)
+}
 
-        new_order.remaining_backer_stake.amount = new_order.remaining_backer_stake.amount.checked_sub(matchable_backer_stake_amount)
-            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("New order stake sub overflow: {}", e) })?;
-        existing_order.remaining_backer_stake.amount = existing_order.remaining_backer_stake.amount.checked_sub(matchable_backer_stake_amount)
-            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Existing order stake sub overflow: {}", e) })?;
+/// Moves open escrow into a matched bet; the contract keeps holding it.
+fn fill_open_escrow(storage: &mut dyn Storage, event_id: u64, owner: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let mut position = load_position(storage, event_id, owner)?;
+    position.open_escrow = position.open_escrow.checked_sub(amount)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Open escrow underflow: {}", e) })?;
+    positions().save(storage, (event_id, owner.clone()), &position)?;
+    Ok(())
+}
 
-        new_order.status = if new_order.remaining_backer_stake.amount.is_zero() { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
-        existing_order.status = if existing_order.remaining_backer_stake.amount.is_zero() { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
-        
-        orders().save(storage, existing_order.id, &existing_order)?;
+fn lock_escrow(storage: &mut dyn Storage, amount: Uint128) -> Result<(), ContractError> {
+    TOTAL_ESCROW.update(storage, |total| -> Result<_, ContractError> {
+        total.checked_add(amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total escrow overflow: {}", e) })
+    })?;
+    Ok(())
+}
 
-        let bet_id = NEXT_BET_ID.update(storage, |id| -> StdResult<_> { Ok(id + 1) })?;
-        
-        let odds_factor_for_liability = new_order.odds.checked_sub(Decimal::one())
-            .map_err(|e: OverflowError| ContractError::CalculationError {msg: format!("Liability odds factor error: {}", e)})?;
-
-        let layer_liability_amount = decimal_times_uint128_trunc(odds_factor_for_liability, matchable_backer_stake_amount)?;
-
-        let (backer_addr, lay_addr, backer_stake_coin, layer_liability_coin) = 
-            if new_order.order_type == OrderType::Back { 
-                (
-                    new_order.owner.clone(),
-                    existing_order.owner.clone(),
-                    Coin { denom: config.betting_denom.clone(), amount: matchable_backer_stake_amount },
-                    Coin { denom: config.betting_denom.clone(), amount: layer_liability_amount } 
-                )
-            } else { 
-                (
-                    existing_order.owner.clone(),
-                    new_order.owner.clone(),
-                    Coin { denom: config.betting_denom.clone(), amount: matchable_backer_stake_amount },
-                    Coin { denom: config.betting_denom.clone(), amount: layer_liability_amount }
-                )
-            };
+fn unlock_escrow(storage: &mut dyn Storage, amount: Uint128) -> Result<(), ContractError> {
+    TOTAL_ESCROW.update(storage, |total| -> Result<_, ContractError> {
+        total.checked_sub(amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total escrow underflow: {}", e) })
+    })?;
+    Ok(())
+}
 
-        let matched_bet = MatchedBet {
-            id: bet_id,
-            event_id: new_order.event_id,
-            backer_addr,
-            lay_addr,
-            backer_stake: backer_stake_coin,
-            layer_liability: layer_liability_coin,
-            outcome_backed: new_order.outcome, 
-            odds: new_order.odds,
-            creation_time: env.block.time,
-        };
-        MATCHED_BETS.save(storage, bet_id, &matched_bet)?;
-        
-        EVENT_TO_MATCHED_BETS.update(storage, new_order.event_id, |bet_ids_opt| -> StdResult<_> {
-            let mut ids = bet_ids_opt.unwrap_or_default();
-            ids.push(bet_id);
-            Ok(ids)
-        })?;
-        newly_matched_bet_ids.push(bet_id);
-    }
-    
-    if matched_any_this_call { 
-         orders().save(storage, new_order.id, &new_order)?;
+/// Credits both sides of a new matched bet to their positions and the outcome's market stats.
+fn record_matched_bet(storage: &mut dyn Storage, bet: &MatchedBet) -> Result<(), ContractError> {
+    let pot = bet.backer_stake.amount.checked_add(bet.layer_liability.amount)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Pot overflow for bet {}: {}", bet.id, e) })?;
+    let backed = bet.outcome_backed as usize;
+
+    let mut backer_position = load_position(storage, bet.event_id, &bet.backer_addr)?;
+    backer_position.matched_backer_stake += bet.backer_stake.amount;
+    backer_position.payout_by_outcome[backed] += pot;
+    positions().save(storage, (bet.event_id, bet.backer_addr.clone()), &backer_position)?;
+
+    // The layer collects the pot if any outcome other than the backed one wins.
+    let mut layer_position = load_position(storage, bet.event_id, &bet.lay_addr)?;
+    layer_position.matched_layer_liability += bet.layer_liability.amount;
+    for (idx, payout) in layer_position.payout_by_outcome.iter_mut().enumerate() {
+        if idx != backed {
+            *payout += pot;
+        }
     }
-    
-    let order_fully_filled = new_order.remaining_backer_stake.amount.is_zero();
-    Ok(MatchResult { newly_matched_bet_ids, order_fully_filled })
+    positions().save(storage, (bet.event_id, bet.lay_addr.clone()), &layer_position)?;
+    record_market_stats(storage, bet)
 }
 
+fn record_market_stats(storage: &mut dyn Storage, bet: &MatchedBet) -> Result<(), ContractError> {
+    MARKET_STATS.update(storage, (bet.event_id, bet.outcome_backed), |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.last_traded_odds = Some(bet.odds);
+        stats.matched_volume += bet.backer_stake.amount;
+        Ok(stats)
+    })?;
+    Ok(())
+}
 
-fn execute_cancel_order(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    order_id: u64,
-) -> Result<Response, ContractError> {
-    let mut order = orders().load(deps.storage, order_id)
-        .map_err(|_| ContractError::OrderNotFound { order_id })?;
+/// Event limits where set, the global ones otherwise.
+fn effective_limits(global: &RiskLimits, event: &RiskLimits) -> RiskLimits {
+    RiskLimits {
+        min_stake: event.min_stake.or(global.min_stake),
+        max_stake: event.max_stake.or(global.max_stake),
+        max_liability_per_user: event.max_liability_per_user.or(global.max_liability_per_user),
+        max_matched_volume: event.max_matched_volume.or(global.max_matched_volume),
+    }
+}
 
-    if order.owner != info.sender {
-        return Err(ContractError::Unauthorized {});
+fn validate_limits(limits: &RiskLimits) -> Result<(), ContractError> {
+    if let (Some(min_stake), Some(max_stake)) = (limits.min_stake, limits.max_stake) {
+        if min_stake > max_stake {
+            return Err(ContractError::InvalidLimits { reason: "min_stake exceeds max_stake".to_string() });
+        }
     }
+    Ok(())
+}
 
-    if order.status == OrderStatus::Filled {
-        return Err(ContractError::CannotCancelFilledOrder { order_id });
+/// Bands must rise above 1.0 and each must span a whole number of its ticks.
+fn validate_odds_ladder(ladder: &[OddsBand]) -> Result<(), ContractError> {
+    if ladder.is_empty() {
+        return Err(ContractError::InvalidOddsLadder { reason: "the ladder needs at least one band".to_string() });
     }
-    if order.status == OrderStatus::Cancelled { 
-        return Ok(Response::new().add_attribute("method", "cancel_order").add_attribute("status", "already_cancelled"));
+    let mut band_start = Decimal::one();
+    for band in ladder {
+        if band.tick.is_zero() || band.up_to <= band_start {
+            return Err(ContractError::InvalidOddsLadder { reason: format!("band up to {} must end above {} with a positive tick", band.up_to, band_start) });
+        }
+        if !is_whole_ticks(band.up_to - band_start, band.tick) {
+            return Err(ContractError::InvalidOddsLadder { reason: format!("band up to {} is not a whole number of {} ticks", band.up_to, band.tick) });
+        }
+        band_start = band.up_to;
     }
-    
-    let config = CONFIG.load(deps.storage)?;
-    let mut refund_messages: Vec<CosmosMsg> = Vec::new();
+    Ok(())
+}
 
-    if !order.remaining_backer_stake.amount.is_zero() {
-        let amount_to_refund: Uint128;
-        match order.order_type {
-            OrderType::Back => { 
-                amount_to_refund = order.remaining_backer_stake.amount;
+fn ensure_on_ladder(ladder: &[OddsBand], odds: Decimal) -> Result<(), ContractError> {
+    let mut band_start = Decimal::one();
+    for band in ladder {
+        if odds <= band.up_to {
+            if is_whole_ticks(odds - band_start, band.tick) {
+                return Ok(());
             }
-            OrderType::Lay => { 
-                let odds_factor = order.odds.checked_sub(Decimal::one())
-                    .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Cancel odds factor error: {}", e) })?;
-                amount_to_refund = decimal_times_uint128_trunc(odds_factor, order.remaining_backer_stake.amount)?;
-            }
-        }
-        if !amount_to_refund.is_zero() {
-            refund_messages.push(CosmosMsg::Bank(BankMsg::Send {
-                to_address: order.owner.to_string(),
-                amount: vec![Coin { denom: config.betting_denom.clone(), amount: amount_to_refund }],
-            }));
+            break;
         }
+        band_start = band.up_to;
     }
-    
-    order.status = OrderStatus::Cancelled;
-    orders().save(deps.storage, order_id, &order)?;
+    Err(ContractError::OddsOffLadder { odds: odds.to_string() })
+}
 
-    Ok(Response::new()
-        .add_messages(refund_messages)
-        .add_attribute("method", "cancel_order")
-        .add_attribute("order_id", order_id.to_string())
-        .add_attribute("refunded_to", order.owner.to_string()))
+fn is_whole_ticks(span: Decimal, tick: Decimal) -> bool {
+    (span.atomics() % tick.atomics()).is_zero()
 }
 
-fn execute_resolve_event(
-    deps: DepsMut,
-    _env: Env, 
-    info: MessageInfo,
-    event_id: u64,
-    winning_outcome: Outcome,
-) -> Result<Response, ContractError> {
+/// Whether every event override is at least as strict as the global limit it replaces.
+fn tightens_limits(global: &RiskLimits, event: &RiskLimits) -> bool {
+    let no_higher = |event_max: Option<Uint128>, global_max: Option<Uint128>| match (event_max, global_max) {
+        (Some(event_max), Some(global_max)) => event_max <= global_max,
+        _ => true,
+    };
+    let no_lower_min = match (event.min_stake, global.min_stake) {
+        (Some(event_min), Some(global_min)) => event_min >= global_min,
+        _ => true,
+    };
+    no_lower_min
+        && no_higher(event.max_stake, global.max_stake)
+        && no_higher(event.max_liability_per_user, global.max_liability_per_user)
+        && no_higher(event.max_matched_volume, global.max_matched_volume)
+}
+
+fn event_matched_volume(storage: &dyn Storage, event_id: u64) -> StdResult<Uint128> {
+    MARKET_STATS
+        .prefix(event_id)
+        .range(storage, None, None, CwOrder::Ascending)
+        .try_fold(Uint128::zero(), |total, item| Ok(total + item?.1.matched_volume))
+}
+
+/// Most an address can lose on an event: the loss of its matched bets under the worst outcome plus
+/// all escrow on its open orders. Matching moves escrow into bets without raising this figure, so
+/// it only has to be checked when orders are placed.
+fn position_exposure(position: &Position) -> Result<Uint128, ContractError> {
+    let at_risk = position.matched_backer_stake.checked_add(position.matched_layer_liability)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Exposure overflow: {}", e) })?;
+    let worst_payout = position.payout_by_outcome.iter().min().copied().unwrap_or_default();
+    Ok(at_risk.saturating_sub(worst_payout) + position.open_escrow)
+}
+
+/// Checks shared by everything that can add to the book or create matches.
+fn ensure_trading_allowed(config: &Config, event: &Event, env: &Env) -> Result<(), ContractError> {
+    if config.paused {
+        return Err(ContractError::ContractPaused {});
+    }
+    if event.status != EventStatus::Open {
+        return Err(ContractError::EventNotOpen { event_id: event.id });
+    }
+    if event.suspended {
+        return Err(ContractError::EventSuspended { event_id: event.id });
+    }
+    if betting_closed(event, env) {
+        return Err(ContractError::BettingClosed { event_id: event.id });
+    }
+    Ok(())
+}
+
+/// Betting also closes as soon as the first oracle has voted.
+fn betting_closed(event: &Event, env: &Env) -> bool {
+    !event.oracle_votes.is_empty() || event.betting_closes_at.is_some_and(|closes_at| env.block.time >= closes_at)
+}
+
+fn oracle_panel(event: &Event) -> &[Addr] {
+    if event.oracles.is_empty() {
+        std::slice::from_ref(&event.oracle)
+    } else {
+        &event.oracles
+    }
+}
+
+/// Who may cancel or suspend an event: always the admin, and the oracle only when it answers the
+/// event alone. A panel acts only through its quorum, and a resolver contract never sends these.
+fn may_manage_event(config: &Config, event: &Event, sender: &Addr) -> bool {
+    let sole_oracle = oracle_panel(event).len() == 1 && event.resolver_market_id.is_none();
+    config.admin == *sender || (sole_oracle && event.oracle == *sender)
+}
+
+/// Whether some outcome can still collect enough votes from the oracles that have not voted yet.
+fn quorum_reachable(event: &Event) -> bool {
+    let mut tally = vec![0u32; event.outcomes.len()];
+    for vote in &event.oracle_votes {
+        tally[vote.outcome as usize] += 1;
+    }
+    let outstanding = (oracle_panel(event).len() - event.oracle_votes.len()) as u32;
+    tally.iter().any(|votes| votes + outstanding >= event.oracle_threshold)
+}
+
+fn resolve_by_passed(event: &Event, env: &Env) -> bool {
+    event.resolve_by.is_some_and(|resolve_by| env.block.time >= resolve_by)
+}
+
+fn ensure_valid_outcome(event: &Event, outcome: OutcomeIndex) -> Result<(), ContractError> {
+    if (outcome as usize) < event.outcomes.len() {
+        Ok(())
+    } else {
+        Err(ContractError::InvalidOutcome { event_id: event.id, outcome })
+    }
+}
+
+/// Resolving or voiding is only allowed while neither has happened yet.
+fn ensure_event_unsettled(event: &Event) -> Result<(), ContractError> {
+    match event.status {
+        EventStatus::Open => Ok(()),
+        _ if event.winning_outcome.is_some() => Err(ContractError::EventAlreadyResolved { event_id: event.id }),
+        _ => Err(ContractError::EventNotOpen { event_id: event.id }),
+    }
+}
+
+/// Whether the event's result is final, so its positions can be paid out.
+fn event_concluded(event: &Event) -> bool {
+    !matches!(event.status, EventStatus::Open | EventStatus::Proposed | EventStatus::Disputed)
+}
+
+struct Claim {
+    payout: Uint128,
+    commission: Uint128,
+}
+
+/// What a position can claim once its event is settled, or `None` while the event is still open.
+/// Commission is taken only from net winnings on a resolved event, so losers, break-even
+/// positions and voided events pay nothing. A settled event without a winning outcome was voided,
+/// so matched stakes are returned as-is. The rate is the one the event was created with.
+fn claimable_amount(event: &Event, position: &Position) -> Result<Option<Claim>, ContractError> {
+    if !event_concluded(event) {
+        return Ok(None);
+    }
+    let matched_at_risk = position.matched_backer_stake.checked_add(position.matched_layer_liability)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Matched stake overflow: {}", e) })?;
+    let (settled_amount, commission) = match event.winning_outcome {
+        Some(winning_outcome) => {
+            let gross = position.payout_by_outcome[winning_outcome as usize];
+            let net_winnings = gross.saturating_sub(matched_at_risk);
+            let commission = net_winnings.multiply_ratio(event.commission_bps as u128, BPS_DENOMINATOR);
+            (gross - commission, commission)
+        }
+        None => (matched_at_risk, Uint128::zero()),
+    };
+    let payout = settled_amount.checked_add(position.open_escrow)
+        .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Claim overflow: {}", e) })?;
+    Ok(Some(Claim { payout, commission }))
+}
+
+fn accrue_fees(storage: &mut dyn Storage, commission: Uint128) -> Result<(), ContractError> {
+    if !commission.is_zero() {
+        ACCRUED_FEES.update(storage, |fees| -> Result<_, ContractError> {
+            fees.checked_add(commission)
+                .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Accrued fees overflow: {}", e) })
+        })?;
+    }
+    Ok(())
+}
+
+/// Up to `limit` of the event's resting orders, and whether that is all of them.
+fn event_book_order_ids(storage: &dyn Storage, event: &Event, limit: usize) -> StdResult<(Vec<u64>, bool)> {
+    let mut order_ids: Vec<u64> = Vec::new();
+    for outcome in 0..event.outcomes.len() as OutcomeIndex {
+        for side in [OrderType::Back, OrderType::Lay] {
+            let keys = ORDER_BOOK
+                .prefix((event.id, outcome, order_type_key(side)))
+                .keys(storage, None, None, CwOrder::Ascending)
+                .take(limit + 1 - order_ids.len());
+            for key in keys {
+                let (_price_key, order_id) = key?;
+                order_ids.push(order_id);
+            }
+            if order_ids.len() > limit {
+                order_ids.truncate(limit);
+                return Ok((order_ids, false));
+            }
+        }
+    }
+    Ok((order_ids, true))
+}
+
+/// Takes a resting order off the book and marks it cancelled, returning it with the escrow it held.
+/// That escrow is left to the caller.
+fn close_book_order(storage: &mut dyn Storage, order_id: u64) -> StdResult<(Order, Uint128)> {
+    let mut order = orders().load(storage, order_id)?;
+    let escrow = order.remaining_escrow;
+    order.remaining_escrow = Uint128::zero();
+    order.status = OrderStatus::Cancelled;
+    orders().save(storage, order_id, &order)?;
+    ORDER_BOOK.remove(storage, book_key(&order));
+    Ok((order, escrow))
+}
+
+fn try_match_order(
+    storage: &mut dyn Storage,
+    env: Env,
+    new_order_id: u64,
+) -> Result<MatchResult, ContractError> {
+    let mut newly_matched_bet_ids = Vec::new();
+    let mut price_improvement_refund = Uint128::zero();
+    let mut new_order = orders().load(storage, new_order_id)?;
+    let config = CONFIG.load(storage)?;
+
+    if new_order.status == OrderStatus::Filled || new_order.remaining_backer_stake.amount.is_zero() { 
+        return Ok(MatchResult { newly_matched_bet_ids, order_fully_filled: true, price_improvement_refund });
+    }
+
+    let counter_order_type = match new_order.order_type {
+        OrderType::Back => OrderType::Lay,
+        OrderType::Lay => OrderType::Back,
+    };
+    let counter_side = (new_order.event_id, new_order.outcome, order_type_key(counter_order_type));
+    let event = EVENTS.load(storage, new_order.event_id)?;
+    // Backer stake the event can still match before reaching its volume limit.
+    let mut volume_capacity = match effective_limits(&config.limits, &event.limits).max_matched_volume {
+        Some(max_volume) => Some(max_volume.saturating_sub(event_matched_volume(storage, event.id)?)),
+        None => None,
+    };
+
+    let mut matched_any_this_call = false;
+    // Walk the opposite side of the book in price-time order, one entry at a time, so that
+    // the work done is proportional to the number of fills rather than to the book size.
+    let mut cursor: Option<(u128, u64)> = None;
+
+    while !new_order.remaining_backer_stake.amount.is_zero() {
+        let next_entry = ORDER_BOOK
+            .prefix(counter_side)
+            .range(storage, cursor.map(Bound::exclusive), None, CwOrder::Ascending)
+            .next()
+            .transpose()?;
+        let Some(((price_key, existing_order_id), existing_owner)) = next_entry else { break };
+        cursor = Some((price_key, existing_order_id));
+
+        if existing_owner == new_order.owner {
+            continue;
+        }
+        let mut existing_order = orders().load(storage, existing_order_id)?;
+        if !is_price_acceptable(&new_order, existing_order.odds) {
+            break;
+        }
+        if order_expired(&existing_order, &env) {
+            continue;
+        }
+        
+        let mut matchable_backer_stake_amount = new_order.remaining_backer_stake.amount.min(existing_order.remaining_backer_stake.amount);
+        if let Some(capacity) = volume_capacity.as_mut() {
+            if capacity.is_zero() {
+                break;
+            }
+            matchable_backer_stake_amount = matchable_backer_stake_amount.min(*capacity);
+            *capacity -= matchable_backer_stake_amount;
+        }
+
+        if matchable_backer_stake_amount.is_zero() {
+            continue;
+        }
+        matched_any_this_call = true;
+
+        new_order.remaining_backer_stake.amount = new_order.remaining_backer_stake.amount.checked_sub(matchable_backer_stake_amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("New order stake sub overflow: {}", e) })?;
+        existing_order.remaining_backer_stake.amount = existing_order.remaining_backer_stake.amount.checked_sub(matchable_backer_stake_amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Existing order stake sub overflow: {}", e) })?;
+
+        new_order.status = if new_order.remaining_backer_stake.amount.is_zero() { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
+        existing_order.status = if existing_order.remaining_backer_stake.amount.is_zero() { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
+        let new_filled_escrow = take_filled_escrow(&mut new_order)?;
+        let existing_filled_escrow = take_filled_escrow(&mut existing_order)?;
+        
+        orders().save(storage, existing_order.id, &existing_order)?;
+        if existing_order.status == OrderStatus::Filled {
+            ORDER_BOOK.remove(storage, book_key(&existing_order));
+        }
+
+        let bet_id = NEXT_BET_ID.update(storage, |id| -> StdResult<_> { Ok(id + 1) })?;
+        
+        // The resting order sets the traded price, so a resting lay's freed escrow is exactly its
+        // liability. An incoming lay filled below its own odds is charged the liability at the
+        // traded price and refunded the rest of what the fill freed.
+        let matched_odds = existing_order.odds;
+        let (backer_addr, lay_addr, layer_liability_amount, improvement_refund) = if new_order.order_type == OrderType::Back { 
+            (new_order.owner.clone(), existing_order.owner.clone(), existing_filled_escrow, Uint128::zero())
+        } else { 
+            let layer_liability_amount = if matched_odds == new_order.odds {
+                new_filled_escrow
+            } else {
+                lay_liability_for(matched_odds, matchable_backer_stake_amount)?.min(new_filled_escrow)
+            };
+            (existing_order.owner.clone(), new_order.owner.clone(), layer_liability_amount, new_filled_escrow - layer_liability_amount)
+        };
+        price_improvement_refund += improvement_refund;
+
+        let matched_bet = MatchedBet {
+            id: bet_id,
+            event_id: new_order.event_id,
+            backer_addr,
+            lay_addr,
+            backer_stake: Coin { denom: config.betting_denom.clone(), amount: matchable_backer_stake_amount },
+            layer_liability: Coin { denom: config.betting_denom.clone(), amount: layer_liability_amount },
+            outcome_backed: new_order.outcome, 
+            odds: matched_odds,
+            creation_time: env.block.time,
+        };
+        matched_bets().save(storage, bet_id, &matched_bet)?;
+        record_matched_bet(storage, &matched_bet)?;
+        fill_open_escrow(storage, new_order.event_id, &new_order.owner, new_filled_escrow - improvement_refund)?;
+        release_open_escrow(storage, new_order.event_id, &new_order.owner, improvement_refund)?;
+        fill_open_escrow(storage, existing_order.event_id, &existing_order.owner, existing_filled_escrow)?;
+
+        newly_matched_bet_ids.push(bet_id);
+    }
+    
+    if matched_any_this_call { 
+         orders().save(storage, new_order.id, &new_order)?;
+    }
+    
+    let order_fully_filled = new_order.remaining_backer_stake.amount.is_zero();
+    if order_fully_filled {
+        ORDER_BOOK.remove(storage, book_key(&new_order));
+    }
+    Ok(MatchResult { newly_matched_bet_ids, order_fully_filled, price_improvement_refund })
+}
+
+
+fn execute_cancel_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+) -> Result<Response, ContractError> {
+    let Some((owner, refund)) = cancel_live_order(deps.storage, &env, &info.sender, order_id)? else {
+        return Ok(Response::new().add_attribute("method", "cancel_order").add_attribute("status", "already_cancelled"));
+    };
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(Response::new()
+        .add_messages(refund_messages(&config, vec![(owner.clone(), refund)]))
+        .add_attribute("method", "cancel_order")
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("refunded_to", owner.to_string()))
+}
+
+fn execute_cancel_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if order_ids.is_empty() || order_ids.len() > MAX_BATCH_ORDERS {
+        return Err(ContractError::InvalidBatchSize { size: order_ids.len(), max: MAX_BATCH_ORDERS });
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut refunds = Vec::new();
+    let mut res = Response::new().add_attribute("method", "cancel_orders");
+    for order_id in order_ids {
+        if let Some(refund) = cancel_live_order(deps.storage, &env, &info.sender, order_id)? {
+            refunds.push(refund);
+            res = res.add_attribute("order_id", order_id.to_string());
+        }
+    }
+    Ok(res.add_messages(refund_messages(&config, refunds)))
+}
+
+/// Cancels up to `MAX_BATCH_ORDERS` of an owner's live orders on open events, optionally only
+/// those on `event_id`. `more_remaining` tells the caller to send it again.
+fn execute_cancel_all_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Option<u64>,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let owner = acting_owner(deps.as_ref(), &env, &info.sender, owner, OperatorScope::Cancel)?;
+
+    let mut open_events: BTreeMap<u64, bool> = BTreeMap::new();
+    let mut order_ids = Vec::new();
+    let mut more_remaining = false;
+    for item in orders().idx.owner.prefix(owner.clone()).range(deps.storage, None, None, CwOrder::Ascending) {
+        let (order_id, order) = item?;
+        if !matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled)
+            || event_id.is_some_and(|id| id != order.event_id)
+        {
+            continue;
+        }
+        let event_open = match open_events.get(&order.event_id) {
+            Some(open) => *open,
+            None => {
+                let open = EVENTS.load(deps.storage, order.event_id)?.status == EventStatus::Open;
+                open_events.insert(order.event_id, open);
+                open
+            }
+        };
+        if !event_open {
+            continue;
+        }
+        if order_ids.len() == MAX_BATCH_ORDERS {
+            more_remaining = true;
+            break;
+        }
+        order_ids.push(order_id);
+    }
+
+    let mut refunds = Vec::new();
+    let mut res = Response::new()
+        .add_attribute("method", "cancel_all_orders")
+        .add_attribute("owner", owner.to_string());
+    for order_id in order_ids {
+        if let Some(refund) = cancel_live_order(deps.storage, &env, &info.sender, order_id)? {
+            refunds.push(refund);
+            res = res.add_attribute("order_id", order_id.to_string());
+        }
+    }
+    Ok(res
+        .add_messages(refund_messages(&config, refunds))
+        .add_attribute("more_remaining", more_remaining.to_string()))
+}
+
+/// Cancel-replace in one step: moves an order's odds and/or unmatched size, settling only the
+/// escrow difference. Shrinking an order at the same odds amends it in place and keeps its queue
+/// position; any other change replaces it with a new order at the back of its price level.
+fn execute_amend_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: u64,
+    new_odds: Option<Decimal>,
+    new_remaining_stake: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut order = orders().load(deps.storage, order_id)
+        .map_err(|_| ContractError::OrderNotFound { order_id })?;
+    let owner = order.owner.clone();
+    if owner != info.sender {
+        ensure_operator(deps.storage, &env, &owner, &info.sender, OperatorScope::Place)?;
+        ensure_operator(deps.storage, &env, &owner, &info.sender, OperatorScope::Cancel)?;
+    }
+    if !matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled) || order_expired(&order, &env) {
+        return Err(ContractError::OrderNotLive { order_id });
+    }
+
+    let odds = new_odds.unwrap_or(order.odds);
+    let stake = new_remaining_stake.unwrap_or(order.remaining_backer_stake.amount);
+    if stake.is_zero() {
+        return Err(ContractError::InvalidStakeAmount {});
+    }
+    if odds <= Decimal::one() {
+        return Err(ContractError::InvalidOdds {});
+    }
+    if odds == order.odds && stake == order.remaining_backer_stake.amount {
+        return Err(ContractError::NothingToAmend { order_id });
+    }
+    let event = EVENTS.load(deps.storage, order.event_id)?;
+    ensure_trading_allowed(&config, &event, &env)?;
+
+    let old_escrow = order.remaining_escrow;
+    let keeps_priority = odds == order.odds && stake < order.remaining_backer_stake.amount;
+    let (amended_order_id, new_escrow, matches) = if keeps_priority {
+        order.initial_backer_stake.amount = order.initial_backer_stake.amount - order.remaining_backer_stake.amount + stake;
+        order.remaining_backer_stake.amount = stake;
+        let new_escrow = order_escrow(order.order_type, order.odds, stake)?;
+        order.remaining_escrow = new_escrow;
+        orders().save(deps.storage, order_id, &order)?;
+        release_open_escrow(deps.storage, order.event_id, &owner, old_escrow - new_escrow)?;
+        let matches = try_match_order(deps.storage, env.clone(), order_id)?;
+        (order_id, new_escrow, matches)
+    } else {
+        close_book_order(deps.storage, order_id)?;
+        release_open_escrow(deps.storage, order.event_id, &owner, old_escrow)?;
+        let time_in_force = order.expires_at.map(|expires| TimeInForce::GoodTilDate { expires });
+        let new_order = NewOrder { event_id: order.event_id, order_type: order.order_type, outcome: order.outcome, stake, odds, time_in_force };
+        let placed = open_order(deps.storage, &env, &config, &owner, &new_order)?;
+        (placed.order_id, placed.escrow, placed.matches)
+    };
+
+    let mut refund = matches.price_improvement_refund;
+    if new_escrow > old_escrow {
+        fund_orders(deps.storage, &info, &config, &owner, new_escrow - old_escrow)?;
+    } else {
+        if !info.funds.is_empty() {
+            return Err(ContractError::InsufficientFundsSent {
+                required: "0".to_string() + &config.betting_denom,
+                sent: info.funds[0].amount.to_string() + &info.funds[0].denom,
+            });
+        }
+        refund += old_escrow - new_escrow;
+    }
+
+    let res = Response::new()
+        .add_messages(refund_messages(&config, vec![(owner.clone(), refund)]))
+        .add_attribute("method", "amend_order")
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("amended_order_id", amended_order_id.to_string())
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("odds", odds.to_string())
+        .add_attribute("remaining_stake", stake.to_string())
+        .add_attribute("priority_kept", keeps_priority.to_string())
+        .add_attribute("refund", refund.to_string());
+    Ok(add_match_attributes(res, &matches))
+}
+
+/// Cancels a live order on behalf of `sender` (its owner or a cancel operator) and releases its
+/// escrow. Returns the owner and amount to refund, or `None` if it was already cancelled.
+fn cancel_live_order(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    order_id: u64,
+) -> Result<Option<(Addr, Uint128)>, ContractError> {
+    let order = orders().load(storage, order_id)
+        .map_err(|_| ContractError::OrderNotFound { order_id })?;
+
+    if order.owner != *sender {
+        ensure_operator(storage, env, &order.owner, sender, OperatorScope::Cancel)?;
+    }
+
+    if order.status == OrderStatus::Filled {
+        return Err(ContractError::CannotCancelFilledOrder { order_id });
+    }
+    if order.status == OrderStatus::Cancelled { 
+        return Ok(None);
+    }
+    release_cancelled_order(storage, order).map(Some)
+}
+
+/// Cancels a live order on an open event and releases its unmatched escrow, returning the owner and
+/// the amount to refund them.
+fn release_cancelled_order(storage: &mut dyn Storage, mut order: Order) -> Result<(Addr, Uint128), ContractError> {
+    // Once an event is settled, unmatched escrow is returned through ClaimWinnings.
+    let event = EVENTS.load(storage, order.event_id)?;
+    if event.status != EventStatus::Open {
+        return Err(ContractError::EventNotOpen { event_id: order.event_id });
+    }
+
+    let amount_to_refund = order.remaining_escrow;
+    release_open_escrow(storage, order.event_id, &order.owner, amount_to_refund)?;
+    
+    order.remaining_escrow = Uint128::zero();
+    order.status = OrderStatus::Cancelled;
+    orders().save(storage, order.id, &order)?;
+    ORDER_BOOK.remove(storage, book_key(&order));
+    Ok((order.owner, amount_to_refund))
+}
+
+/// Permissionless cleanup of good-til-date orders past their expiry; each owner is refunded in one
+/// transfer. Orders that are no longer live or not yet expired are skipped, so one order expiring a
+/// block later does not revert the batch; it fails only if it cancelled nothing.
+fn execute_cancel_expired_orders(
+    deps: DepsMut,
+    env: Env,
+    order_ids: Vec<u64>,
+) -> Result<Response, ContractError> {
+    if order_ids.is_empty() || order_ids.len() > MAX_BATCH_ORDERS {
+        return Err(ContractError::InvalidBatchSize { size: order_ids.len(), max: MAX_BATCH_ORDERS });
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut refunds = Vec::new();
+    let mut first_unexpired = None;
+    let mut res = Response::new().add_attribute("method", "cancel_expired_orders");
+    for order_id in order_ids {
+        let order = orders().load(deps.storage, order_id)
+            .map_err(|_| ContractError::OrderNotFound { order_id })?;
+        if !matches!(order.status, OrderStatus::Open | OrderStatus::PartiallyFilled) {
+            continue;
+        }
+        if !order_expired(&order, &env) {
+            first_unexpired.get_or_insert(order_id);
+            continue;
+        }
+        refunds.push(release_cancelled_order(deps.storage, order)?);
+        res = res.add_attribute("order_id", order_id.to_string());
+    }
+    if let Some(order_id) = first_unexpired.filter(|_| refunds.is_empty()) {
+        return Err(ContractError::OrderNotExpired { order_id });
+    }
+    Ok(res.add_messages(refund_messages(&config, refunds)))
+}
+
+/// One bank transfer per owner for the summed refunds, skipping owners owed nothing.
+fn refund_messages(config: &Config, refunds: Vec<(Addr, Uint128)>) -> Vec<CosmosMsg> {
+    let mut totals: BTreeMap<Addr, Uint128> = BTreeMap::new();
+    for (owner, amount) in refunds {
+        *totals.entry(owner).or_default() += amount;
+    }
+    totals
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(owner, amount)| CosmosMsg::Bank(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![Coin { denom: config.betting_denom.clone(), amount }],
+        }))
+        .collect()
+}
+
+fn execute_propose_resolution(
+    deps: DepsMut,
+    env: Env, 
+    info: MessageInfo,
+    event_id: u64,
+    winning_outcome: OutcomeIndex,
+) -> Result<Response, ContractError> {
+    let event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+
+    if event.price_condition.is_some() {
+        return Err(ContractError::PriceMarket { event_id });
+    }
+    if !oracle_panel(&event).contains(&info.sender) {
+        return Err(ContractError::OracleMismatch { event_id });
+    }
+    cast_oracle_vote(deps, env, event, info.sender, winning_outcome, "propose_resolution")
+}
+
+/// Permissionless: reads the outcome from an event whose oracle is a resolver contract, or derives
+/// it from the price oracle for a price market once its observation time has passed. The answer is
+/// cast as that contract's vote, so it goes through the same challenge period as any other.
+fn execute_resolve_from_oracle(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
+    let event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    ensure_event_unsettled(&event)?;
+
+    let (oracle, winning_outcome, observed_price) = match (&event.price_condition, &event.resolver_market_id) {
+        (Some(condition), _) => {
+            if env.block.time < condition.observation_time {
+                return Err(ContractError::ObservationTimeNotReached { event_id });
+            }
+            let price_oracle = CONFIG.load(deps.storage)?.price_oracle
+                .ok_or(ContractError::NoPriceOracle {})?;
+            let response: PriceAtResponse = deps.querier.query_wasm_smart(
+                price_oracle.clone(),
+                &PriceFeedQueryMsg::PriceAt { feed_id: condition.price_feed_id.clone(), observation_time: condition.observation_time },
+            )?;
+            let price = response.price
+                .ok_or(ContractError::OracleOutcomeUnavailable { event_id })?;
+            let winning_outcome = if price_condition_met(condition, price) { OUTCOME_YES } else { OUTCOME_NO };
+            (price_oracle, winning_outcome, Some(price))
+        }
+        (None, Some(market_id)) => {
+            let response: ResolverOutcomeResponse = deps.querier
+                .query_wasm_smart(event.oracle.clone(), &ResolverQueryMsg::EventOutcome { market_id: market_id.clone() })?;
+            let winning_outcome = response.winning_outcome
+                .ok_or(ContractError::OracleOutcomeUnavailable { event_id })?;
+            (event.oracle.clone(), winning_outcome, None)
+        }
+        (None, None) => return Err(ContractError::NotResolverEvent { event_id }),
+    };
+    let res = cast_oracle_vote(deps, env, event, oracle, winning_outcome, "resolve_from_oracle")?;
+    Ok(match observed_price {
+        Some(price) => res.add_attribute("observed_price", price.to_string()),
+        None => res,
+    })
+}
+
+/// Records `oracle`'s vote. Once `oracle_threshold` oracles agree, their outcome is proposed and
+/// only becomes final through `FinalizeResolution` once the challenge period has passed without a
+/// dispute, so a wrong or compromised oracle key cannot move the pots alone. Votes that conflict
+/// so that no outcome can reach the threshold send the event straight to `Disputed`.
+fn cast_oracle_vote(
+    deps: DepsMut,
+    env: Env,
+    mut event: Event,
+    oracle: Addr,
+    winning_outcome: OutcomeIndex,
+    method: &str,
+) -> Result<Response, ContractError> {
+    let event_id = event.id;
+    ensure_event_unsettled(&event)?;
+    if resolve_by_passed(&event, &env) {
+        return Err(ContractError::ResolveByPassed { event_id });
+    }
+    ensure_valid_outcome(&event, winning_outcome)?;
+    if event.oracle_votes.iter().any(|vote| vote.oracle == oracle) {
+        return Err(ContractError::AlreadyVoted { event_id });
+    }
+    event.oracle_votes.push(OracleVote { oracle: oracle.clone(), outcome: winning_outcome });
+    let votes_for_outcome = event.oracle_votes.iter().filter(|vote| vote.outcome == winning_outcome).count() as u32;
+
+    let mut res = Response::new()
+        .add_attribute("method", method)
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("oracle", oracle.to_string())
+        .add_attribute("winning_outcome", format!("{:?}", winning_outcome))
+        .add_attribute("votes_for_outcome", format!("{}/{}", votes_for_outcome, event.oracle_threshold));
+    if votes_for_outcome >= event.oracle_threshold {
+        let config = CONFIG.load(deps.storage)?;
+        let challenge_ends_at = env.block.time.plus_seconds(config.challenge_period_secs);
+        event.status = EventStatus::Proposed;
+        event.proposal = Some(ResolutionProposal {
+            outcome: winning_outcome,
+            challenge_ends_at,
+            disputer: None,
+            bond: Uint128::zero(),
+        });
+        res = res.add_attribute("challenge_ends_at", challenge_ends_at.to_string());
+    } else if !quorum_reachable(&event) {
+        event.status = EventStatus::Disputed;
+    }
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    Ok(res.add_attribute("status", format!("{:?}", event.status)))
+}
+
+fn execute_dispute_resolution(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    let mut proposal = match (&event.status, event.proposal.clone()) {
+        (EventStatus::Proposed, Some(proposal)) => proposal,
+        _ => return Err(ContractError::NoResolutionProposed { event_id }),
+    };
+    if env.block.time >= proposal.challenge_ends_at {
+        return Err(ContractError::ChallengePeriodEnded { event_id });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let sent_coin = single_coin_sent(&info, &config.betting_denom)?;
+    if sent_coin.amount != config.dispute_bond {
+        return Err(ContractError::IncorrectDisputeBond {
+            required: config.dispute_bond.to_string() + &config.betting_denom,
+            sent: sent_coin.amount.to_string() + &sent_coin.denom,
+        });
+    }
+
+    proposal.disputer = Some(info.sender.clone());
+    proposal.bond = config.dispute_bond;
+    event.status = EventStatus::Disputed;
+    event.proposal = Some(proposal);
+    EVENTS.save(deps.storage, event_id, &event)?;
+    TOTAL_BONDS.update(deps.storage, |total| -> Result<_, ContractError> {
+        total.checked_add(config.dispute_bond)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total bonds overflow: {}", e) })
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "dispute_resolution")
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("disputer", info.sender.to_string())
+        .add_attribute("bond", config.dispute_bond.to_string() + &config.betting_denom))
+}
+
+/// Permissionless: makes an undisputed proposal final once its challenge period is over.
+fn execute_finalize_resolution(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    let proposal = match (&event.status, event.proposal.take()) {
+        (EventStatus::Proposed, Some(proposal)) => proposal,
+        _ => return Err(ContractError::NoResolutionProposed { event_id }),
+    };
+    if env.block.time < proposal.challenge_ends_at {
+        return Err(ContractError::ChallengePeriodActive { event_id });
+    }
+
+    event.status = EventStatus::Resolved;
+    event.winning_outcome = Some(proposal.outcome);
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "finalize_resolution")
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("winning_outcome", format!("{:?}", proposal.outcome)))
+}
+
+/// Admin or arbitrator settles a dispute by resolving the event to `winning_outcome`, or voiding it
+/// when `None`. The bond goes back to the disputer if the ruling overturns the proposal and is
+/// otherwise added to the accrued fees. Events disputed by conflicting oracle votes carry no bond.
+fn execute_rule_on_dispute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event_id: u64,
+    winning_outcome: Option<OutcomeIndex>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender && config.arbitrator.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    if event.status != EventStatus::Disputed {
+        return Err(ContractError::EventNotDisputed { event_id });
+    }
+    let proposal = event.proposal.take();
+    if let Some(outcome) = winning_outcome {
+        ensure_valid_outcome(&event, outcome)?;
+    }
+
+    event.status = if winning_outcome.is_some() { EventStatus::Resolved } else { EventStatus::Cancelled };
+    event.winning_outcome = winning_outcome;
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    let mut res = Response::new()
+        .add_attribute("method", "rule_on_dispute")
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("ruling", winning_outcome.map_or("void".to_string(), |outcome| outcome.to_string()));
+    let Some(proposal) = proposal else {
+        return Ok(res);
+    };
+    TOTAL_BONDS.update(deps.storage, |total| -> Result<_, ContractError> {
+        total.checked_sub(proposal.bond)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total bonds underflow: {}", e) })
+    })?;
+    let dispute_upheld = winning_outcome != Some(proposal.outcome);
+    if dispute_upheld {
+        if let Some(disputer) = proposal.disputer.filter(|_| !proposal.bond.is_zero()) {
+            res = res.add_message(BankMsg::Send {
+                to_address: disputer.to_string(),
+                amount: vec![Coin { denom: config.betting_denom.clone(), amount: proposal.bond }],
+            });
+        }
+        res = res.add_attribute("bond_returned", proposal.bond.to_string() + &config.betting_denom);
+    } else {
+        accrue_fees(deps.storage, proposal.bond)?;
+        res = res.add_attribute("bond_slashed", proposal.bond.to_string() + &config.betting_denom);
+    }
+    Ok(res)
+}
+
+fn execute_cancel_event(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if !may_manage_event(&config, &event, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_event_unsettled(&event)?;
+
+    event.status = EventStatus::Cancelled;
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_event")
+        .add_attribute("event_id", event_id.to_string()))
+}
+
+fn execute_claim_winnings(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
+    let event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    let mut position = positions().may_load(deps.storage, (event_id, info.sender.clone()))?
+        .ok_or(ContractError::NothingToClaim { event_id })?;
+    if position.claimed {
+        return Err(ContractError::AlreadyClaimed { event_id });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let claim = claimable_amount(&event, &position)?
+        .ok_or(ContractError::EventNotSettled { event_id })?;
+    let payout_amount = claim.payout;
+    if payout_amount.is_zero() {
+        return Err(ContractError::NothingToClaim { event_id });
+    }
+
+    position.claimed = true;
+    positions().save(deps.storage, (event_id, info.sender.clone()), &position)?;
+    accrue_fees(deps.storage, claim.commission)?;
+    unlock_escrow(deps.storage, payout_amount + claim.commission)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin { denom: config.betting_denom.clone(), amount: payout_amount }],
+        })
+        .add_attribute("method", "claim_winnings")
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("claimant", info.sender.to_string())
+        .add_attribute("payout_amount", payout_amount.to_string() + &config.betting_denom)
+        .add_attribute("commission", claim.commission.to_string() + &config.betting_denom))
+}
+
+/// Permissionless crank that settles a resolved or cancelled event in bounded batches. Each call
+/// pays the next unclaimed positions (one transfer per address covering its matched bets and
+/// unmatched escrow), then closes the event's leftover book orders whose escrow was just paid out.
+fn execute_settle_event(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    event_id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    match event.status {
+        _ if !event_concluded(&event) => return Err(ContractError::EventNotSettled { event_id }),
+        EventStatus::Settled => return Err(ContractError::EventAlreadySettled { event_id }),
+        _ => event.status = EventStatus::Settling,
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_SETTLE_LIMIT).min(MAX_SETTLE_LIMIT) as usize;
+    let mut payout_messages: Vec<CosmosMsg> = Vec::new();
+    let mut response_attributes_map: Vec<(String, String)> = vec![
+        ("method".to_string(), "settle_event".to_string()),
+        ("event_id".to_string(), event_id.to_string()),
+    ];
+
+    let start_bound = event.settlement_cursor.clone().map(Bound::exclusive);
+    let batch: Vec<Position> = positions()
+        .prefix(event_id)
+        .range(deps.storage, start_bound, None, CwOrder::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_owner, position)| position))
+        .collect::<StdResult<_>>()?;
+    let positions_done = batch.len() < limit;
+
+    for mut position in batch {
+        event.settlement_cursor = Some(position.owner.clone());
+        if position.claimed {
+            continue;
+        }
+        let (payout_amount, commission) = claimable_amount(&event, &position)?
+            .map_or((Uint128::zero(), Uint128::zero()), |claim| (claim.payout, claim.commission));
+        position.claimed = true;
+        positions().save(deps.storage, (event_id, position.owner.clone()), &position)?;
+        accrue_fees(deps.storage, commission)?;
+        unlock_escrow(deps.storage, payout_amount + commission)?;
+        if !payout_amount.is_zero() {
+            payout_messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: position.owner.to_string(),
+                amount: vec![Coin { denom: config.betting_denom.clone(), amount: payout_amount }],
+            }));
+            response_attributes_map.push(("payout_winner".to_string(), position.owner.to_string()));
+            response_attributes_map.push(("payout_amount".to_string(), payout_amount.to_string() + &config.betting_denom));
+        }
+    }
+
+    // Leftover orders are closed only once every position has been paid, so their escrow is never
+    // released twice. Closing removes them from the book, so no cursor is needed for this pass.
+    let mut orders_done = true;
+    if positions_done {
+        let (order_ids, book_drained) = event_book_order_ids(deps.storage, &event, limit)?;
+        orders_done = book_drained;
+        for order_id in order_ids {
+            close_book_order(deps.storage, order_id)?;
+            response_attributes_map.push(("refunded_open_order_id".to_string(), order_id.to_string()));
+        }
+    }
+
+    if positions_done && orders_done {
+        event.status = EventStatus::Settled;
+    }
+    EVENTS.save(deps.storage, event_id, &event)?;
+    response_attributes_map.push(("settlement_status".to_string(), format!("{:?}", event.status)));
+
+    Ok(Response::new()
+        .add_messages(payout_messages)
+        .add_attributes(response_attributes_map))
+}
+
+/// Permissionless crank that returns unmatched escrow once betting has closed, so users do not have
+/// to wait for the oracle to get back funds that can no longer be matched.
+fn execute_refund_unmatched(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    event_id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    // Once the event is resolved or voided, unmatched escrow is paid out with the position.
+    if event.status != EventStatus::Open {
+        return Err(ContractError::EventNotOpen { event_id });
+    }
+    if !betting_closed(&event, &env) {
+        return Err(ContractError::BettingStillOpen { event_id });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_SETTLE_LIMIT).min(MAX_SETTLE_LIMIT) as usize;
+    let (order_ids, book_drained) = event_book_order_ids(deps.storage, &event, limit)?;
+    let mut refund_messages: Vec<CosmosMsg> = Vec::new();
+    let mut res = Response::new()
+        .add_attribute("method", "refund_unmatched")
+        .add_attribute("event_id", event_id.to_string());
+
+    for order_id in order_ids {
+        let (order, amount_to_refund) = close_book_order(deps.storage, order_id)?;
+        release_open_escrow(deps.storage, event_id, &order.owner, amount_to_refund)?;
+        if !amount_to_refund.is_zero() {
+            refund_messages.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: order.owner.to_string(),
+                amount: vec![Coin { denom: config.betting_denom.clone(), amount: amount_to_refund }],
+            }));
+        }
+        res = res.add_attribute("refunded_open_order_id", order_id.to_string());
+    }
+
+    Ok(res
+        .add_messages(refund_messages)
+        .add_attribute("book_drained", book_drained.to_string()))
+}
+
+/// Permissionless fallback that voids an event its oracle failed to resolve by `resolve_by`.
+/// Claims and `SettleEvent` then refund it exactly like an event cancelled by the oracle.
+fn execute_void_expired_event(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    event_id: u64,
+) -> Result<Response, ContractError> {
     let mut event = EVENTS.load(deps.storage, event_id)
         .map_err(|_| ContractError::EventNotFound { event_id })?;
+    ensure_event_unsettled(&event)?;
+    if !resolve_by_passed(&event, &env) {
+        return Err(ContractError::ResolveByNotReached { event_id });
+    }
+
+    event.status = EventStatus::Cancelled;
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "void_expired_event")
+        .add_attribute("event_id", event_id.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    commission_bps: Option<u16>,
+    fee_collector: Option<String>,
+    challenge_period_secs: Option<u64>,
+    dispute_bond: Option<Uint128>,
+    arbitrator: Option<String>,
+    price_oracle: Option<String>,
+    limits: Option<RiskLimits>,
+    odds_ladder: Option<Vec<OddsBand>>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    if event.oracle != info.sender {
-        return Err(ContractError::OracleMismatch { event_id });
+    let mut res = Response::new().add_attribute("method", "update_config");
+    if let Some(commission_bps) = commission_bps {
+        validate_commission(commission_bps)?;
+        config.commission_bps = commission_bps;
+        res = res.add_attribute("commission_bps", commission_bps.to_string());
+    }
+    if let Some(addr_str) = fee_collector {
+        config.fee_collector = deps.api.addr_validate(&addr_str)?;
+        res = res.add_attribute("fee_collector", config.fee_collector.to_string());
+    }
+    if let Some(challenge_period_secs) = challenge_period_secs {
+        config.challenge_period_secs = challenge_period_secs;
+        res = res.add_attribute("challenge_period_secs", challenge_period_secs.to_string());
+    }
+    if let Some(dispute_bond) = dispute_bond {
+        validate_dispute_bond(dispute_bond)?;
+        config.dispute_bond = dispute_bond;
+        res = res.add_attribute("dispute_bond", dispute_bond.to_string());
+    }
+    if let Some(addr_str) = arbitrator {
+        let arbitrator = deps.api.addr_validate(&addr_str)?;
+        res = res.add_attribute("arbitrator", arbitrator.to_string());
+        config.arbitrator = Some(arbitrator);
+    }
+    if let Some(addr_str) = price_oracle {
+        let price_oracle = deps.api.addr_validate(&addr_str)?;
+        res = res.add_attribute("price_oracle", price_oracle.to_string());
+        config.price_oracle = Some(price_oracle);
     }
-    if event.status == EventStatus::Resolved {
-        return Err(ContractError::EventAlreadyResolved { event_id });
+    if let Some(limits) = limits {
+        validate_limits(&limits)?;
+        res = res.add_attribute("limits", format!("{:?}", limits));
+        config.limits = limits;
     }
+    if let Some(odds_ladder) = odds_ladder {
+        validate_odds_ladder(&odds_ladder)?;
+        res = res.add_attribute("odds_ladder_bands", odds_ladder.len().to_string());
+        config.odds_ladder = odds_ladder;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    Ok(res)
+}
 
-    event.status = EventStatus::Resolved;
-    event.winning_outcome = Some(winning_outcome);
-    EVENTS.save(deps.storage, event_id, &event)?;
+fn execute_propose_new_admin(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_ADMIN.save(deps.storage, &new_admin)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "propose_new_admin")
+        .add_attribute("admin", config.admin.to_string())
+        .add_attribute("pending_admin", new_admin.to_string()))
+}
+
+fn execute_accept_admin(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN.may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if pending_admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
 
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_admin = config.admin;
+    config.admin = pending_admin;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "accept_admin")
+        .add_attribute("previous_admin", previous_admin.to_string())
+        .add_attribute("admin", config.admin.to_string()))
+}
+
+fn execute_grant_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    scope: OperatorScope,
+    expires: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    if operator == info.sender || expires.is_some_and(|expires| expires <= env.block.time) {
+        return Err(ContractError::InvalidOperatorGrant {});
+    }
+    OPERATORS.save(deps.storage, (&info.sender, &operator), &OperatorGrant { scope, expires })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "grant_operator")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("operator", operator.to_string())
+        .add_attribute("scope", format!("{:?}", scope))
+        .add_attribute("expires", expires.map_or("never".to_string(), |expires| expires.to_string())))
+}
+
+fn execute_revoke_operator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_operator")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("operator", operator.to_string()))
+}
+
+fn execute_deposit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let mut payout_messages: Vec<CosmosMsg> = Vec::new();
-    let mut response_attributes_map: Vec<(String, String)> = vec![
-        ("method".to_string(), "resolve_event".to_string()),
-        ("event_id".to_string(), event_id.to_string()),
-        ("winning_outcome".to_string(), format!("{:?}", winning_outcome)),
-    ];
+    let sent_coin = single_coin_sent(&info, &config.betting_denom)?;
+    let balance = DEPOSITS.update(deps.storage, &info.sender, |balance| -> Result<_, ContractError> {
+        balance.unwrap_or_default().checked_add(sent_coin.amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Deposit overflow: {}", e) })
+    })?;
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> Result<_, ContractError> {
+        total.checked_add(sent_coin.amount)
+            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Total deposits overflow: {}", e) })
+    })?;
 
-    let bet_ids = EVENT_TO_MATCHED_BETS.load(deps.storage, event_id)?;
-    for bet_id in bet_ids {
-        let bet = MATCHED_BETS.load(deps.storage, bet_id)?;
-        
-        let winner_addr: Addr;
-        let total_pot_amount = bet.backer_stake.amount.checked_add(bet.layer_liability.amount)
-            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Resolve total pot overflow for bet {}: {}", bet_id, e) })?;
-        let payout_coin = Coin { denom: config.betting_denom.clone(), amount: total_pot_amount };
+    Ok(Response::new()
+        .add_attribute("method", "deposit")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("amount", sent_coin.amount.to_string() + &config.betting_denom)
+        .add_attribute("balance", balance.to_string() + &config.betting_denom))
+}
 
-        if bet.outcome_backed == winning_outcome { 
-            winner_addr = bet.backer_addr;
-        } else { 
-            winner_addr = bet.lay_addr;
-        }
-        
-        payout_messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: winner_addr.to_string(),
-            amount: vec![payout_coin.clone()],
-        }));
-        response_attributes_map.push(("payout_bet_id".to_string(), bet_id.to_string()));
-        response_attributes_map.push(("payout_winner".to_string(), winner_addr.to_string()));
-        response_attributes_map.push(("payout_amount".to_string(), payout_coin.amount.to_string() + &payout_coin.denom));
-    }
-
-    let orders_to_process: Vec<Order> = orders()
-        .range(deps.storage, None, None, CwOrder::Ascending)
-        .filter_map(|res| res.ok().map(|(_order_id_u64, order_val)| order_val))
-        .collect();
-    
-    for mut order in orders_to_process {
-        if order.event_id == event_id && (order.status == OrderStatus::Open || order.status == OrderStatus::PartiallyFilled) {
-            if !order.remaining_backer_stake.amount.is_zero() {
-                let amount_to_refund: Uint128;
-                match order.order_type {
-                    OrderType::Back => amount_to_refund = order.remaining_backer_stake.amount,
-                    OrderType::Lay => {
-                        let odds_factor = order.odds.checked_sub(Decimal::one())
-                            .map_err(|e: OverflowError| ContractError::CalculationError { msg: format!("Refund odds factor error for order {}: {}", order.id, e) })?;
-                        amount_to_refund = decimal_times_uint128_trunc(odds_factor, order.remaining_backer_stake.amount)?; 
-                    }
-                }
-                if !amount_to_refund.is_zero() {
-                    payout_messages.push(CosmosMsg::Bank(BankMsg::Send {
-                        to_address: order.owner.to_string(),
-                        amount: vec![Coin { denom: config.betting_denom.clone(), amount: amount_to_refund }],
-                    }));
-                    response_attributes_map.push(("refunded_open_order_id".to_string(), order.id.to_string()));
-                }
-            }
-            order.status = OrderStatus::Cancelled; 
-            orders().save(deps.storage, order.id, &order)?;
-        }
+fn execute_withdraw_deposit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidStakeAmount {});
     }
+    let config = CONFIG.load(deps.storage)?;
+    debit_deposit(deps.storage, &config, &info.sender, amount)?;
 
     Ok(Response::new()
-        .add_messages(payout_messages)
-        .add_attributes(response_attributes_map))
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin { denom: config.betting_denom.clone(), amount }],
+        })
+        .add_attribute("method", "withdraw_deposit")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("amount", amount.to_string() + &config.betting_denom))
+}
+
+fn execute_set_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", if paused { "pause" } else { "unpause" })
+        .add_attribute("paused", paused.to_string()))
+}
+
+fn execute_set_event_suspended(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event_id: u64,
+    suspended: bool,
+) -> Result<Response, ContractError> {
+    let mut event = EVENTS.load(deps.storage, event_id)
+        .map_err(|_| ContractError::EventNotFound { event_id })?;
+    let config = CONFIG.load(deps.storage)?;
+    if !may_manage_event(&config, &event, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if event.status != EventStatus::Open {
+        return Err(ContractError::EventNotOpen { event_id });
+    }
+    event.suspended = suspended;
+    EVENTS.save(deps.storage, event_id, &event)?;
+
+    Ok(Response::new()
+        .add_attribute("method", if suspended { "suspend_event" } else { "resume_event" })
+        .add_attribute("event_id", event_id.to_string())
+        .add_attribute("suspended", suspended.to_string()))
+}
+
+fn execute_withdraw_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.admin != info.sender && config.fee_collector != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let accrued_fees = ACCRUED_FEES.load(deps.storage)?;
+    if accrued_fees.is_zero() {
+        return Err(ContractError::NoFeesToWithdraw {});
+    }
+    ACCRUED_FEES.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: config.fee_collector.to_string(),
+            amount: vec![Coin { denom: config.betting_denom.clone(), amount: accrued_fees }],
+        })
+        .add_attribute("method", "withdraw_fees")
+        .add_attribute("fee_collector", config.fee_collector.to_string())
+        .add_attribute("amount", accrued_fees.to_string() + &config.betting_denom))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::GetFees {} => to_json_binary(&query_fees(deps)?),
+        QueryMsg::GetOddsLadder {} => to_json_binary(&query_odds_ladder(deps)?),
+        QueryMsg::CheckEscrowInvariant {} => to_json_binary(&query_escrow_invariant(deps, env)?),
         QueryMsg::GetEvent { event_id } => to_json_binary(&query_event(deps, event_id)?),
+        QueryMsg::GetOracleVotes { event_id } => to_json_binary(&query_oracle_votes(deps, event_id)?),
         QueryMsg::ListEvents { start_after, limit, filter_status } => 
             to_json_binary(&query_list_events(deps, start_after, limit, filter_status)?),
         QueryMsg::GetOrder { order_id } => to_json_binary(&query_order(deps, order_id)?),
@@ -521,6 +2177,17 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query_list_orders_by_event(deps, event_id, start_after, limit, filter_order_type, filter_outcome)?),
         QueryMsg::ListMatchedBetsByEvent { event_id, start_after, limit } =>
             to_json_binary(&query_list_matched_bets_by_event(deps, event_id, start_after, limit)?),
+        QueryMsg::GetOrderBook { event_id, outcome, depth } =>
+            to_json_binary(&query_order_book(deps, env, event_id, outcome, depth)?),
+        QueryMsg::ListOrdersByOwner { owner, status_filter, start_after, limit } =>
+            to_json_binary(&query_list_orders_by_owner(deps, owner, status_filter, start_after, limit)?),
+        QueryMsg::ListBetsByUser { addr, role, start_after, limit } =>
+            to_json_binary(&query_list_bets_by_user(deps, addr, role, start_after, limit)?),
+        QueryMsg::GetUserPosition { addr, event_id } => to_json_binary(&query_user_position(deps, addr, event_id)?),
+        QueryMsg::GetOperator { owner, operator } => to_json_binary(&query_operator(deps, owner, operator)?),
+        QueryMsg::GetDeposit { addr } => to_json_binary(&query_deposit(deps, addr)?),
+        QueryMsg::ListUnclaimed { addr, start_after, limit } =>
+            to_json_binary(&query_list_unclaimed(deps, addr, start_after, limit)?),
     }
 }
 
@@ -532,18 +2199,70 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(ConfigResponse {
         admin: config.admin,
         betting_denom: config.betting_denom,
+        commission_bps: config.commission_bps,
+        fee_collector: config.fee_collector,
+        pending_admin: PENDING_ADMIN.may_load(deps.storage)?,
+        paused: config.paused,
+        challenge_period_secs: config.challenge_period_secs,
+        dispute_bond: config.dispute_bond,
+        arbitrator: config.arbitrator,
+        price_oracle: config.price_oracle,
+        limits: config.limits,
         next_event_id,
         next_order_id,
         next_bet_id,
     })
 }
 
+fn query_fees(deps: Deps) -> StdResult<FeesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let accrued_fees = ACCRUED_FEES.may_load(deps.storage)?.unwrap_or_default();
+    Ok(FeesResponse {
+        commission_bps: config.commission_bps,
+        fee_collector: config.fee_collector,
+        accrued_fees: Coin { denom: config.betting_denom, amount: accrued_fees },
+    })
+}
+
+fn query_odds_ladder(deps: Deps) -> StdResult<OddsLadderResponse> {
+    let bands = CONFIG.load(deps.storage)?.odds_ladder;
+    let min_odds = bands.first().map_or(Decimal::one(), |band| Decimal::one() + band.tick);
+    let max_odds = bands.last().map_or(Decimal::one(), |band| band.up_to);
+    Ok(OddsLadderResponse { bands, min_odds, max_odds })
+}
+
+/// Compares what the contract owes on orders, bets, fees, deposits and pending dispute bonds with
+/// what it actually holds. A healthy contract may hold more, e.g. funds sent to it directly, but
+/// never less.
+fn query_escrow_invariant(deps: Deps, env: Env) -> StdResult<EscrowInvariantResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_escrow = TOTAL_ESCROW.may_load(deps.storage)?.unwrap_or_default();
+    let accrued_fees = ACCRUED_FEES.may_load(deps.storage)?.unwrap_or_default();
+    let total_deposits = TOTAL_DEPOSITS.may_load(deps.storage)?.unwrap_or_default();
+    let dispute_bonds = TOTAL_BONDS.may_load(deps.storage)?.unwrap_or_default();
+    let balance = deps.querier.query_balance(env.contract.address, config.betting_denom)?;
+    let owed = total_escrow.checked_add(accrued_fees)?
+        .checked_add(total_deposits)?
+        .checked_add(dispute_bonds)?;
+    let holds = balance.amount >= owed;
+    Ok(EscrowInvariantResponse { total_escrow, accrued_fees, total_deposits, dispute_bonds, balance, holds })
+}
+
 fn query_event(deps: Deps, event_id: u64) -> StdResult<EventResponse> {
     let event = EVENTS.load(deps.storage, event_id)
         .map_err(|_| StdError::not_found(format!("event {}", event_id)))?;
     Ok(EventResponse { event })
 }
 
+fn query_oracle_votes(deps: Deps, event_id: u64) -> StdResult<OracleVotesResponse> {
+    let event = EVENTS.load(deps.storage, event_id)?;
+    Ok(OracleVotesResponse {
+        oracles: oracle_panel(&event).to_vec(),
+        threshold: event.oracle_threshold,
+        votes: event.oracle_votes,
+    })
+}
+
 fn query_list_events(
     deps: Deps,
     start_after: Option<u64>,
@@ -581,29 +2300,41 @@ fn query_list_orders_by_event(
     start_after: Option<u64>, 
     limit: Option<u32>,
     filter_order_type: Option<OrderType>,
-    filter_outcome: Option<Outcome>,
+    filter_outcome: Option<OutcomeIndex>,
 ) -> StdResult<OrdersResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start_bound = start_after.map(Bound::exclusive); 
-    
-    let orders_list: Vec<Order> = orders()
-        .range(deps.storage, start_bound, None, CwOrder::Ascending) 
-        .filter_map(|item| {
-            item.ok().and_then(|(_pk_vec, order)| { 
-                if order.event_id == event_id &&
-                   (filter_order_type.is_none() || Some(order.order_type) == filter_order_type) &&
-                   (filter_outcome.is_none() || Some(order.outcome) == filter_outcome) &&
-                   (order.status == OrderStatus::Open || order.status == OrderStatus::PartiallyFilled)
-                {
-                    Some(order)
-                } else {
-                    None
+    let Some(event) = EVENTS.may_load(deps.storage, event_id)? else {
+        return Ok(OrdersResponse { orders: vec![] });
+    };
+
+    // Live orders are exactly those on the book, so only the event's book sides are walked. Keys
+    // are ordered by price there, so ids are gathered and sorted before paginating.
+    let outcomes = match filter_outcome {
+        Some(outcome) => vec![outcome],
+        None => (0..event.outcomes.len() as OutcomeIndex).collect(),
+    };
+    let sides = match filter_order_type {
+        Some(order_type) => vec![order_type],
+        None => vec![OrderType::Back, OrderType::Lay],
+    };
+    let mut order_ids: Vec<u64> = Vec::new();
+    for outcome in outcomes {
+        for side in &sides {
+            for key in ORDER_BOOK.prefix((event_id, outcome, order_type_key(*side))).keys(deps.storage, None, None, CwOrder::Ascending) {
+                let (_price_key, order_id) = key?;
+                if start_after.is_none_or(|start_after| order_id > start_after) {
+                    order_ids.push(order_id);
                 }
-            })
-        })
-        .take(limit)
-        .collect();
+            }
+        }
+    }
+    order_ids.sort_unstable();
 
+    let orders_list = order_ids
+        .into_iter()
+        .take(limit)
+        .map(|order_id| orders().load(deps.storage, order_id))
+        .collect::<StdResult<Vec<Order>>>()?;
     Ok(OrdersResponse { orders: orders_list })
 }
 
@@ -614,22 +2345,373 @@ fn query_list_matched_bets_by_event(
     limit: Option<u32>,
 ) -> StdResult<MatchedBetsResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let bet_ids_for_event = EVENT_TO_MATCHED_BETS.may_load(deps.storage, event_id)?.unwrap_or_default();
-    
-    let relevant_bet_ids = bet_ids_for_event.into_iter()
-        .filter(|&id| start_after.map_or(true, |sa| id > sa)) 
-        .take(limit);
+    let matched_bets_list = matched_bets()
+        .idx
+        .event
+        .prefix(event_id)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, CwOrder::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, bet)| bet))
+        .collect::<StdResult<Vec<MatchedBet>>>()?;
+    Ok(MatchedBetsResponse { matched_bets: matched_bets_list })
+}
+
+fn query_order_book(
+    deps: Deps,
+    env: Env,
+    event_id: u64,
+    outcome: OutcomeIndex,
+    depth: Option<u32>,
+) -> StdResult<OrderBookResponse> {
+    let depth = depth.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let stats = MARKET_STATS.may_load(deps.storage, (event_id, outcome))?.unwrap_or_default();
+    Ok(OrderBookResponse {
+        event_id,
+        outcome,
+        available_to_back: book_levels(deps.storage, &env, (event_id, outcome, order_type_key(OrderType::Lay)), depth)?,
+        available_to_lay: book_levels(deps.storage, &env, (event_id, outcome, order_type_key(OrderType::Back)), depth)?,
+        last_traded_odds: stats.last_traded_odds,
+        matched_volume: stats.matched_volume,
+    })
+}
 
-    let mut matched_bets_list: Vec<MatchedBet> = Vec::with_capacity(limit);
-    for bet_id in relevant_bet_ids {
-        if let Ok(bet) = MATCHED_BETS.load(deps.storage, bet_id) {
-            matched_bets_list.push(bet);
+/// Sums one side of the book into its best `depth` price levels, leaving out expired orders.
+fn book_levels(storage: &dyn Storage, env: &Env, side: (u64, u8, u8), depth: usize) -> StdResult<Vec<PriceLevel>> {
+    let mut levels: Vec<PriceLevel> = Vec::new();
+    for entry in ORDER_BOOK.prefix(side).range(storage, None, None, CwOrder::Ascending) {
+        let ((_, order_id), _) = entry?;
+        let order = orders().load(storage, order_id)?;
+        if order_expired(&order, env) {
+            continue;
+        }
+        match levels.last_mut() {
+            Some(level) if level.odds == order.odds => {
+                level.stake += order.remaining_backer_stake.amount;
+                level.order_count += 1;
+            }
+            _ => {
+                if levels.len() == depth {
+                    break;
+                }
+                levels.push(PriceLevel { odds: order.odds, stake: order.remaining_backer_stake.amount, order_count: 1 });
+            }
         }
     }
+    Ok(levels)
+}
+
+fn query_list_orders_by_owner(
+    deps: Deps,
+    owner: String,
+    status_filter: Option<OrderStatus>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let orders_list = orders()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, start_after.map(Bound::exclusive), None, CwOrder::Ascending)
+        .filter(|item| item.as_ref().map_or(true, |(_, order)| status_filter.is_none_or(|status| order.status == status)))
+        .take(limit)
+        .map(|item| item.map(|(_, order)| order))
+        .collect::<StdResult<Vec<Order>>>()?;
+    Ok(OrdersResponse { orders: orders_list })
+}
+
+fn query_list_bets_by_user(
+    deps: Deps,
+    addr: String,
+    role: BetRole,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MatchedBetsResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let bets = matched_bets();
+    let page = |index: &MultiIndex<Addr, MatchedBet, u64>| -> StdResult<Vec<MatchedBet>> {
+        index
+            .prefix(addr.clone())
+            .range(deps.storage, start_after.map(Bound::exclusive), None, CwOrder::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, bet)| bet))
+            .collect()
+    };
+
+    let matched_bets_list = match role {
+        BetRole::Backer => page(&bets.idx.backer)?,
+        BetRole::Layer => page(&bets.idx.layer)?,
+        BetRole::Any => {
+            let mut both = page(&bets.idx.backer)?;
+            both.extend(page(&bets.idx.layer)?);
+            both.sort_by_key(|bet| bet.id);
+            both.dedup_by_key(|bet| bet.id);
+            both.truncate(limit);
+            both
+        }
+    };
     Ok(MatchedBetsResponse { matched_bets: matched_bets_list })
 }
 
+fn query_user_position(deps: Deps, addr: String, event_id: u64) -> StdResult<UserPositionResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let event = EVENTS.load(deps.storage, event_id)?;
+    let position = positions()
+        .may_load(deps.storage, (event_id, addr.clone()))?
+        .unwrap_or_else(|| Position::new(event_id, addr.clone(), event.outcomes.len()));
+
+    let to_signed = |amount: Uint128| -> StdResult<Int128> {
+        Int128::try_from(amount).map_err(|e| StdError::generic_err(e.to_string()))
+    };
+    let at_risk = to_signed(position.matched_backer_stake + position.matched_layer_liability)?;
+    let profit_by_outcome = position.payout_by_outcome
+        .iter()
+        .map(|payout| Ok(to_signed(*payout)? - at_risk))
+        .collect::<StdResult<Vec<Int128>>>()?;
+
+    Ok(UserPositionResponse {
+        event_id,
+        addr,
+        total_staked: position.matched_backer_stake,
+        total_liability: position.matched_layer_liability,
+        open_escrow: position.open_escrow,
+        profit_by_outcome,
+        claimed: position.claimed,
+    })
+}
+
+fn query_operator(deps: Deps, owner: String, operator: String) -> StdResult<OperatorResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let operator = deps.api.addr_validate(&operator)?;
+    Ok(OperatorResponse { grant: OPERATORS.may_load(deps.storage, (&owner, &operator))? })
+}
+
+fn query_deposit(deps: Deps, addr: String) -> StdResult<DepositResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let config = CONFIG.load(deps.storage)?;
+    let amount = DEPOSITS.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(DepositResponse { amount: Coin { denom: config.betting_denom, amount } })
+}
+
+fn query_list_unclaimed(
+    deps: Deps,
+    addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<UnclaimedResponse> {
+    let owner = deps.api.addr_validate(&addr)?;
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_bound = start_after.map(|event_id| Bound::exclusive((event_id, owner.clone())));
+
+    let mut unclaimed: Vec<UnclaimedAmount> = Vec::new();
+    for item in positions()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(deps.storage, start_bound, None, CwOrder::Ascending)
+    {
+        let (_pk, position) = item?;
+        if position.claimed {
+            continue;
+        }
+        let event = EVENTS.load(deps.storage, position.event_id)?;
+        let amount = claimable_amount(&event, &position)
+            .map_err(|e| StdError::generic_err(e.to_string()))?
+            .map(|claim| claim.payout);
+        if let Some(amount) = amount.filter(|amount| !amount.is_zero()) {
+            unclaimed.push(UnclaimedAmount {
+                event_id: position.event_id,
+                amount: Coin { denom: config.betting_denom.clone(), amount },
+            });
+            if unclaimed.len() >= limit {
+                break;
+            }
+        }
+    }
+    Ok(UnclaimedResponse { unclaimed })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: InstantiateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default().add_attribute("method", "migrate"))
-}
\ No newline at end of file
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::MigrationError { msg: format!("Cannot migrate from contract {}", stored.contract) });
+    }
+    let from_version = parse_version(&stored.version)?;
+    if from_version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::MigrationError { msg: format!("Cannot downgrade from {} to {}", stored.version, CONTRACT_VERSION) });
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version.clone())
+        .add_attribute("to_version", CONTRACT_VERSION);
+    if from_version < (0, 2, 0) {
+        res = res.add_attribute("migrated_bets", migrate_from_v0_1(deps.storage)?.to_string());
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
+}
+
+fn parse_version(version: &str) -> Result<(u64, u64, u64), ContractError> {
+    let invalid = || ContractError::MigrationError { msg: format!("Invalid contract version {}", version) };
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().map_err(|_| invalid()));
+    let parsed = (
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+        parts.next().ok_or_else(invalid)??,
+    );
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(parsed)
+}
+
+/// Rewrites a v0.1.0 store in the current layout. v0.1.0 paid out every bet and cancelled leftover
+/// orders when an event resolved, so resolved events become `Settled` with nothing owed. On open
+/// events, live orders are put back on the book and positions are rebuilt from orders and bets.
+/// Returns the number of matched bets migrated.
+fn migrate_from_v0_1(storage: &mut dyn Storage) -> Result<usize, ContractError> {
+    let legacy_config = v0_1::CONFIG.load(storage)?;
+    CONFIG.save(storage, &Config {
+        fee_collector: legacy_config.admin.clone(),
+        admin: legacy_config.admin,
+        betting_denom: legacy_config.betting_denom,
+        commission_bps: 0,
+        paused: false,
+        challenge_period_secs: DEFAULT_CHALLENGE_PERIOD_SECS,
+        dispute_bond: Uint128::new(DEFAULT_DISPUTE_BOND),
+        arbitrator: None,
+        price_oracle: None,
+        limits: RiskLimits::default(),
+        odds_ladder: default_odds_ladder(),
+    })?;
+    ACCRUED_FEES.save(storage, &Uint128::zero())?;
+    TOTAL_ESCROW.save(storage, &Uint128::zero())?;
+    TOTAL_DEPOSITS.save(storage, &Uint128::zero())?;
+    TOTAL_BONDS.save(storage, &Uint128::zero())?;
+
+    let legacy_events = v0_1::EVENTS
+        .range(storage, None, None, CwOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (event_id, legacy) in legacy_events {
+        let status = match legacy.status {
+            EventStatus::Resolved => EventStatus::Settled,
+            status => status,
+        };
+        EVENTS.save(storage, event_id, &Event {
+            id: legacy.id,
+            creator: legacy.creator,
+            description: legacy.description,
+            oracle: legacy.oracle,
+            status,
+            winning_outcome: legacy.winning_outcome.map(OutcomeIndex::from),
+            betting_closes_at: legacy.resolution_deadline,
+            resolve_by: None,
+            creation_time: legacy.creation_time,
+            commission_bps: 0,
+            outcomes: default_outcomes(),
+            suspended: false,
+            settlement_cursor: None,
+            proposal: None,
+            oracles: vec![],
+            oracle_threshold: 1,
+            oracle_votes: vec![],
+            resolver_market_id: None,
+            price_condition: None,
+            limits: RiskLimits::default(),
+        })?;
+    }
+
+    let legacy_orders = v0_1::orders()
+        .range(storage, None, None, CwOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (order_id, legacy) in legacy_orders {
+        let event = EVENTS.load(storage, legacy.event_id)?;
+        let live = matches!(legacy.status, OrderStatus::Open | OrderStatus::PartiallyFilled);
+        // Unmatched escrow on events that are no longer open was already returned.
+        let remaining_escrow = if live && event.status == EventStatus::Open {
+            order_escrow(legacy.order_type, legacy.odds, legacy.remaining_backer_stake.amount)?
+        } else {
+            Uint128::zero()
+        };
+        let order = Order {
+            id: legacy.id,
+            event_id: legacy.event_id,
+            owner: legacy.owner,
+            order_type: legacy.order_type,
+            outcome: legacy.outcome.into(),
+            initial_backer_stake: legacy.initial_backer_stake,
+            remaining_backer_stake: legacy.remaining_backer_stake,
+            odds: legacy.odds,
+            remaining_escrow,
+            creation_time: legacy.creation_time,
+            status: legacy.status,
+            expires_at: None,
+        };
+        // Removing the v0.1 order also removes its entries in both v0.1 indexes, including the
+        // dropped event/outcome one. `save` then finds no old value and writes the owner index afresh.
+        v0_1::orders().remove(storage, order_id)?;
+        orders().save(storage, order_id, &order)?;
+        if event.status == EventStatus::Open {
+            if live {
+                ORDER_BOOK.save(storage, book_key(&order), &order.owner)?;
+            }
+            add_open_escrow(storage, &event, &order.owner, remaining_escrow)?;
+        }
+    }
+
+    let legacy_bets = v0_1::MATCHED_BETS
+        .range(storage, None, None, CwOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let migrated_bets = legacy_bets.len();
+    // Rewritten in the current format but left unindexed until `index_matched_bets` below.
+    const CONVERTED_BETS: Map<u64, MatchedBet> = Map::new("matched_bets");
+    for (bet_id, legacy) in legacy_bets {
+        let bet = MatchedBet {
+            id: legacy.id,
+            event_id: legacy.event_id,
+            backer_addr: legacy.backer_addr,
+            lay_addr: legacy.lay_addr,
+            backer_stake: legacy.backer_stake,
+            layer_liability: legacy.layer_liability,
+            outcome_backed: legacy.outcome_backed.into(),
+            odds: legacy.odds,
+            creation_time: legacy.creation_time,
+        };
+        CONVERTED_BETS.save(storage, bet_id, &bet)?;
+        if EVENTS.load(storage, bet.event_id)?.status == EventStatus::Open {
+            record_matched_bet(storage, &bet)?;
+            lock_escrow(storage, bet.backer_stake.amount + bet.layer_liability.amount)?;
+        } else {
+            record_market_stats(storage, &bet)?;
+        }
+    }
+
+    index_matched_bets(storage)?;
+    Ok(migrated_bets)
+}
+
+/// Indexes the matched bets listed in `EVENT_TO_MATCHED_BETS` and drops the lists. Returns the
+/// number of bets indexed.
+fn index_matched_bets(storage: &mut dyn Storage) -> Result<usize, ContractError> {
+    let bet_lists = EVENT_TO_MATCHED_BETS
+        .range(storage, None, None, CwOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut indexed_bets = 0;
+    for (event_id, bet_ids) in bet_lists {
+        for bet_id in bet_ids {
+            let bet = matched_bets().load(storage, bet_id)?;
+            // With no old value every index entry is written; any that already existed are
+            // rewritten unchanged.
+            matched_bets().replace(storage, bet_id, Some(&bet), None)?;
+            indexed_bets += 1;
+        }
+        EVENT_TO_MATCHED_BETS.remove(storage, event_id);
+    }
+    Ok(indexed_bets)
+}
@@ -33,11 +33,124 @@ pub enum ContractError {
     #[error("InvalidOdds: Odds must be greater than 1.0")]
     InvalidOdds {},
 
+    #[error("OddsOffLadder: Odds of {odds} are not a price on the odds ladder")]
+    OddsOffLadder { odds: String },
+
+    #[error("InvalidOddsLadder: {reason}")]
+    InvalidOddsLadder { reason: String },
+
     #[error("CannotCancelFilledOrder: Order {order_id} has been fully matched")]
     CannotCancelFilledOrder { order_id: u64 },
 
-    #[error("DeadlinePassed: The resolution deadline has passed")]
-    DeadlinePassed {},
+    #[error("InvalidExpiry: A good-til-date order must expire in the future")]
+    InvalidExpiry {},
+
+    #[error("PostOnlyWouldMatch: A post-only order cannot match on placement")]
+    PostOnlyWouldMatch {},
+
+    #[error("FillOrKillNotFilled: A fill-or-kill order could not be matched in full")]
+    FillOrKillNotFilled {},
+
+    #[error("NoLiquidityWithinOdds: Nothing on event {event_id} can be matched within the worst acceptable odds")]
+    NoLiquidityWithinOdds { event_id: u64 },
+
+    #[error("OrderNotExpired: Order {order_id} has not expired")]
+    OrderNotExpired { order_id: u64 },
+
+    #[error("OrderNotLive: Order {order_id} is filled or cancelled")]
+    OrderNotLive { order_id: u64 },
+
+    #[error("NothingToAmend: The amendment leaves order {order_id} unchanged")]
+    NothingToAmend { order_id: u64 },
+
+    #[error("InvalidLimits: {reason}")]
+    InvalidLimits { reason: String },
+
+    #[error("StakeBelowMinimum: Stake {stake} is below the minimum of {min}")]
+    StakeBelowMinimum { stake: String, min: String },
+
+    #[error("StakeAboveMaximum: Stake {stake} is above the maximum of {max}")]
+    StakeAboveMaximum { stake: String, max: String },
+
+    #[error("LiabilityLimitExceeded: Exposure of {exposure} on event {event_id} would exceed the limit of {limit}")]
+    LiabilityLimitExceeded { event_id: u64, exposure: String, limit: String },
+
+    #[error("EventVolumeLimitReached: Event {event_id} has reached its matched volume limit")]
+    EventVolumeLimitReached { event_id: u64 },
+
+    #[error("InvalidBatchSize: A batch must hold between 1 and {max} orders, got {size}")]
+    InvalidBatchSize { size: usize, max: usize },
+
+    #[error("InvalidOperatorGrant: An operator must be another address and expire in the future")]
+    InvalidOperatorGrant {},
+
+    #[error("OperatorFundsNotAccepted: Orders placed by an operator are funded from the owner's deposit")]
+    OperatorFundsNotAccepted {},
+
+    #[error("InsufficientDeposit: Required {required}, available {available}")]
+    InsufficientDeposit { required: String, available: String },
+
+    #[error("ContractPaused: Trading is paused")]
+    ContractPaused {},
+
+    #[error("EventSuspended: Trading on event {event_id} is suspended")]
+    EventSuspended { event_id: u64 },
+
+    #[error("BettingClosed: Betting on event {event_id} has closed")]
+    BettingClosed { event_id: u64 },
+
+    #[error("BettingStillOpen: Betting on event {event_id} has not closed yet")]
+    BettingStillOpen { event_id: u64 },
+
+    #[error("InvalidSchedule: Betting must close in the future and no later than the resolve-by time")]
+    InvalidSchedule {},
+
+    #[error("ResolveByPassed: Event {event_id} was not resolved in time and can only be voided")]
+    ResolveByPassed { event_id: u64 },
+
+    #[error("ResolveByNotReached: Event {event_id} can still be resolved by its oracle")]
+    ResolveByNotReached { event_id: u64 },
+
+    #[error("InvalidOracleSet: {reason}")]
+    InvalidOracleSet { reason: String },
+
+    #[error("AlreadyVoted: This oracle has already voted on event {event_id}")]
+    AlreadyVoted { event_id: u64 },
+    #[error("InvalidDisputeBond: The dispute bond must be positive")]
+    InvalidDisputeBond {},
+
+    #[error("IncorrectDisputeBond: Required exactly {required}, Sent {sent}")]
+    IncorrectDisputeBond { required: String, sent: String },
+
+    #[error("NotResolverEvent: Event {event_id} is not resolved by an oracle contract")]
+    NotResolverEvent { event_id: u64 },
+
+    #[error("OracleOutcomeUnavailable: The oracle contract has no outcome for event {event_id} yet")]
+    OracleOutcomeUnavailable { event_id: u64 },
+
+    #[error("InvalidPriceCondition: {reason}")]
+    InvalidPriceCondition { reason: String },
+
+    #[error("PriceMarket: Event {event_id} is resolved from its price feed, not by oracle votes")]
+    PriceMarket { event_id: u64 },
+
+    #[error("ObservationTimeNotReached: The price for event {event_id} has not been observed yet")]
+    ObservationTimeNotReached { event_id: u64 },
+
+    #[error("NoPriceOracle: No price oracle contract is configured")]
+    NoPriceOracle {},
+
+    #[error("NoResolutionProposed: Event {event_id} has no undisputed resolution proposal")]
+    NoResolutionProposed { event_id: u64 },
+
+    #[error("ChallengePeriodActive: The resolution of event {event_id} can still be disputed")]
+    ChallengePeriodActive { event_id: u64 },
+
+    #[error("ChallengePeriodEnded: The challenge period for event {event_id} has ended")]
+    ChallengePeriodEnded { event_id: u64 },
+
+    #[error("EventNotDisputed: Event {event_id} has no pending dispute")]
+    EventNotDisputed { event_id: u64 },
 
     #[error("InvalidDescription: Description cannot be empty")]
     InvalidDescription {},
@@ -45,6 +158,15 @@ pub enum ContractError {
     #[error("IdenticalOracleAndCreator: Oracle cannot be the same as the creator if explicitly set to a different address (for future external oracle integrations)")]
     IdenticalOracleAndCreator {},
 
+    #[error("InvalidOutcomeCount: Events need between 2 and 64 outcomes, got {count}")]
+    InvalidOutcomeCount { count: usize },
+
+    #[error("InvalidOutcomeName: Outcome name '{name}' is empty or duplicated")]
+    InvalidOutcomeName { name: String },
+
+    #[error("InvalidOutcome: Event {event_id} has no outcome with index {outcome}")]
+    InvalidOutcome { event_id: u64, outcome: u8 },
+
     #[error("InvalidOrderTypeForMatching: Order types are not compatible for matching")]
     InvalidOrderTypeForMatching {},
 
@@ -66,6 +188,27 @@ pub enum ContractError {
     #[error("CalculationError: {msg}")]
     CalculationError { msg: String },
 
+    #[error("EventNotSettled: Event {event_id} has not been resolved or cancelled yet")]
+    EventNotSettled { event_id: u64 },
+
+    #[error("EventAlreadySettled: Event {event_id} has already been fully settled")]
+    EventAlreadySettled { event_id: u64 },
+
+    #[error("NothingToClaim: No funds to claim for event {event_id}")]
+    NothingToClaim { event_id: u64 },
+
+    #[error("AlreadyClaimed: Funds for event {event_id} have already been claimed")]
+    AlreadyClaimed { event_id: u64 },
+
+    #[error("InvalidCommission: Commission of {commission_bps} bps exceeds the maximum of {max_bps} bps")]
+    InvalidCommission { commission_bps: u16, max_bps: u16 },
+
+    #[error("NoFeesToWithdraw: There are no accrued fees to withdraw")]
+    NoFeesToWithdraw {},
+
+    #[error("NoPendingAdmin: No admin transfer has been proposed")]
+    NoPendingAdmin {},
+
     #[error("MigrationError: {msg}")]
     MigrationError { msg: String },
 }
\ No newline at end of file
@@ -7,5 +7,8 @@ pub mod state;
 
 pub use crate::error::ContractError;
 
+#[cfg(test)]
+pub mod mocks;
+
 #[cfg(test)] // Ensures this module is only compiled for tests
 pub mod tests;  // This tells Rust to look for src/tests.rs or src/tests/mod.rs
\ No newline at end of file
@@ -0,0 +1,76 @@
+// src/mocks.rs
+//! Stand-ins for external contracts the betting contract queries, for use with `cw-multi-test`.
+
+pub mod resolver {
+    use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    use cw_storage_plus::Map;
+    use serde::{Deserialize, Serialize};
+
+    use crate::msg::{OutcomeIndex, ResolverOutcomeResponse, ResolverQueryMsg};
+
+    const OUTCOMES: Map<&str, OutcomeIndex> = Map::new("outcomes");
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        SetOutcome { market_id: String, winning_outcome: OutcomeIndex },
+    }
+
+    pub fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::SetOutcome { market_id, winning_outcome } => {
+                OUTCOMES.save(deps.storage, &market_id, &winning_outcome)?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: ResolverQueryMsg) -> StdResult<Binary> {
+        match msg {
+            ResolverQueryMsg::EventOutcome { market_id } => to_json_binary(&ResolverOutcomeResponse {
+                winning_outcome: OUTCOMES.may_load(deps.storage, &market_id)?,
+            }),
+        }
+    }
+}
+
+pub mod price_feed {
+    use cosmwasm_std::{to_json_binary, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Timestamp};
+    use cw_storage_plus::Map;
+    use serde::{Deserialize, Serialize};
+
+    use crate::msg::{PriceAtResponse, PriceFeedQueryMsg};
+
+    const PRICES: Map<(&str, u64), Decimal> = Map::new("prices");
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        SetPrice { feed_id: String, observation_time: Timestamp, price: Decimal },
+    }
+
+    pub fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::SetPrice { feed_id, observation_time, price } => {
+                PRICES.save(deps.storage, (&feed_id, observation_time.seconds()), &price)?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: PriceFeedQueryMsg) -> StdResult<Binary> {
+        match msg {
+            PriceFeedQueryMsg::PriceAt { feed_id, observation_time } => to_json_binary(&PriceAtResponse {
+                price: PRICES.may_load(deps.storage, (&feed_id, observation_time.seconds()))?,
+            }),
+        }
+    }
+}
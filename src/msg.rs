@@ -1,14 +1,20 @@
-use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Int128, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cosmwasm_schema::QueryResponses; // Added for QueryResponses
 
-use crate::state::{Event, MatchedBet, Order};
+use crate::state::{Event, MatchedBet, OperatorGrant, OracleVote, Order};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub admin: Option<String>, 
     pub betting_denom: String, 
+    pub commission_bps: Option<u16>,
+    pub fee_collector: Option<String>,
+    pub challenge_period_secs: Option<u64>,
+    pub dispute_bond: Option<Uint128>,
+    pub arbitrator: Option<String>,
+    pub price_oracle: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -17,21 +23,122 @@ pub enum ExecuteMsg {
     CreateEvent {
         description: String,
         oracle_addr: Option<String>, 
-        resolution_deadline: Option<Timestamp>, 
+        oracles: Option<Vec<String>>,
+        oracle_threshold: Option<u32>,
+        resolver_market_id: Option<String>,
+        price_condition: Option<PriceCondition>,
+        limits: Option<RiskLimits>,
+        betting_closes_at: Option<Timestamp>,
+        resolve_by: Option<Timestamp>,
+        outcomes: Option<Vec<String>>,
     },
     PlaceOrder {
         event_id: u64,
         order_type: OrderType,
-        outcome: Outcome,
+        outcome: OutcomeIndex,
         stake: Uint128, 
         odds: Decimal,  
+        time_in_force: Option<TimeInForce>,
+        owner: Option<String>,
+    },
+    PlaceMarketOrder {
+        event_id: u64,
+        order_type: OrderType,
+        outcome: OutcomeIndex,
+        stake: Uint128,
+        worst_odds: Decimal,
+        owner: Option<String>,
+    },
+    PlaceOrders {
+        orders: Vec<NewOrder>,
+        owner: Option<String>,
     },
     CancelOrder {
         order_id: u64,
     },
-    ResolveEvent {
+    AmendOrder {
+        order_id: u64,
+        new_odds: Option<Decimal>,
+        new_remaining_stake: Option<Uint128>,
+    },
+    CancelOrders {
+        order_ids: Vec<u64>,
+    },
+    CancelAllOrders {
+        event_id: Option<u64>,
+        owner: Option<String>,
+    },
+    CancelExpiredOrders {
+        order_ids: Vec<u64>,
+    },
+    ProposeResolution {
+        event_id: u64,
+        winning_outcome: OutcomeIndex,
+    },
+    ResolveFromOracle {
+        event_id: u64,
+    },
+    DisputeResolution {
+        event_id: u64,
+    },
+    FinalizeResolution {
+        event_id: u64,
+    },
+    RuleOnDispute {
+        event_id: u64,
+        winning_outcome: Option<OutcomeIndex>,
+    },
+    CancelEvent {
+        event_id: u64,
+    },
+    ClaimWinnings {
+        event_id: u64,
+    },
+    SettleEvent {
+        event_id: u64,
+        limit: Option<u32>,
+    },
+    RefundUnmatched {
+        event_id: u64,
+        limit: Option<u32>,
+    },
+    VoidExpiredEvent {
+        event_id: u64,
+    },
+    UpdateConfig {
+        commission_bps: Option<u16>,
+        fee_collector: Option<String>,
+        challenge_period_secs: Option<u64>,
+        dispute_bond: Option<Uint128>,
+        arbitrator: Option<String>,
+        price_oracle: Option<String>,
+        limits: Option<RiskLimits>,
+        odds_ladder: Option<Vec<OddsBand>>,
+    },
+    WithdrawFees {},
+    ProposeNewAdmin {
+        new_admin: String,
+    },
+    AcceptAdmin {},
+    GrantOperator {
+        operator: String,
+        scope: OperatorScope,
+        expires: Option<Timestamp>,
+    },
+    RevokeOperator {
+        operator: String,
+    },
+    Deposit {},
+    WithdrawDeposit {
+        amount: Uint128,
+    },
+    Pause {},
+    Unpause {},
+    SuspendEvent {
+        event_id: u64,
+    },
+    ResumeEvent {
         event_id: u64,
-        winning_outcome: Outcome,
     },
 }
 
@@ -40,10 +147,20 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     GetConfig {},
+    #[returns(FeesResponse)]
+    GetFees {},
+    #[returns(OddsLadderResponse)]
+    GetOddsLadder {},
+    #[returns(EscrowInvariantResponse)]
+    CheckEscrowInvariant {},
     #[returns(EventResponse)]
     GetEvent {
         event_id: u64,
     },
+    #[returns(OracleVotesResponse)]
+    GetOracleVotes {
+        event_id: u64,
+    },
     #[returns(EventsResponse)]
     ListEvents {
         start_after: Option<u64>, 
@@ -60,7 +177,7 @@ pub enum QueryMsg {
         start_after: Option<u64>, 
         limit: Option<u32>,
         filter_order_type: Option<OrderType>,
-        filter_outcome: Option<Outcome>,
+        filter_outcome: Option<OutcomeIndex>,
     },
     #[returns(MatchedBetsResponse)]
     ListMatchedBetsByEvent {
@@ -68,22 +185,107 @@ pub enum QueryMsg {
         start_after: Option<u64>, 
         limit: Option<u32>,
     },
+    #[returns(OrderBookResponse)]
+    GetOrderBook {
+        event_id: u64,
+        outcome: OutcomeIndex,
+        depth: Option<u32>,
+    },
+    #[returns(OrdersResponse)]
+    ListOrdersByOwner {
+        owner: String,
+        status_filter: Option<OrderStatus>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(MatchedBetsResponse)]
+    ListBetsByUser {
+        addr: String,
+        role: BetRole,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(UserPositionResponse)]
+    GetUserPosition {
+        addr: String,
+        event_id: u64,
+    },
+    #[returns(OperatorResponse)]
+    GetOperator {
+        owner: String,
+        operator: String,
+    },
+    #[returns(DepositResponse)]
+    GetDeposit {
+        addr: String,
+    },
+    #[returns(UnclaimedResponse)]
+    ListUnclaimed {
+        addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub admin: Addr,
     pub betting_denom: String,
+    pub commission_bps: u16,
+    pub fee_collector: Addr,
+    pub pending_admin: Option<Addr>,
+    pub paused: bool,
+    pub challenge_period_secs: u64,
+    pub dispute_bond: Uint128,
+    pub arbitrator: Option<Addr>,
+    pub price_oracle: Option<Addr>,
+    pub limits: RiskLimits,
     pub next_event_id: u64,
     pub next_order_id: u64,
     pub next_bet_id: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeesResponse {
+    pub commission_bps: u16,
+    pub fee_collector: Addr,
+    pub accrued_fees: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OddsLadderResponse {
+    pub bands: Vec<OddsBand>,
+    pub min_odds: Decimal,
+    pub max_odds: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowInvariantResponse {
+    pub total_escrow: Uint128,
+    pub accrued_fees: Uint128,
+    pub total_deposits: Uint128,
+    /// Bonds posted on disputes that have not been ruled on yet.
+    pub dispute_bonds: Uint128,
+    pub balance: Coin,
+    /// Whether the balance covers all escrow, accrued fees, deposits and pending dispute bonds.
+    pub holds: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct EventResponse {
     pub event: Event,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OracleVotesResponse {
+    pub oracles: Vec<Addr>,
+    pub threshold: u32,
+    pub votes: Vec<OracleVote>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct EventsResponse {
     pub events: Vec<Event>,
@@ -109,17 +311,174 @@ pub struct MatchedBetsResponse {
     pub matched_bets: Vec<MatchedBet>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceLevel {
+    pub odds: Decimal,
+    pub stake: Uint128,
+    pub order_count: u32,
+}
+
+/// Book for one outcome. `available_to_back` is resting lay stake a backer can take, best (highest)
+/// odds first; `available_to_lay` is resting back stake, best (lowest) odds first.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OrderBookResponse {
+    pub event_id: u64,
+    pub outcome: OutcomeIndex,
+    pub available_to_back: Vec<PriceLevel>,
+    pub available_to_lay: Vec<PriceLevel>,
+    pub last_traded_odds: Option<Decimal>,
+    pub matched_volume: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserPositionResponse {
+    pub event_id: u64,
+    pub addr: Addr,
+    pub total_staked: Uint128,
+    pub total_liability: Uint128,
+    pub open_escrow: Uint128,
+    /// Profit or loss from matched bets if the outcome at this index wins, before commission.
+    pub profit_by_outcome: Vec<Int128>,
+    pub claimed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorResponse {
+    pub grant: Option<OperatorGrant>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositResponse {
+    pub amount: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnclaimedAmount {
+    pub event_id: u64,
+    pub amount: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnclaimedResponse {
+    pub unclaimed: Vec<UnclaimedAmount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolverQueryMsg {
+    #[returns(ResolverOutcomeResponse)]
+    EventOutcome {
+        market_id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolverOutcomeResponse {
+    pub winning_outcome: Option<OutcomeIndex>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceFeedQueryMsg {
+    #[returns(PriceAtResponse)]
+    PriceAt {
+        feed_id: String,
+        observation_time: Timestamp,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceAtResponse {
+    pub price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceCondition {
+    pub price_feed_id: String,
+    pub comparator: PriceComparator,
+    pub threshold: Decimal,
+    pub observation_time: Timestamp,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
-pub enum Outcome {
-    Yes,
-    No,
+#[serde(rename_all = "snake_case")]
+pub enum PriceComparator {
+    Above,
+    AtOrAbove,
+    Below,
+    AtOrBelow,
 }
 
+/// Position of an outcome in its event's `outcomes` list.
+pub type OutcomeIndex = u8;
+
+/// Outcome indexes of the default binary (Yes/No) market.
+pub const OUTCOME_YES: OutcomeIndex = 0;
+pub const OUTCOME_NO: OutcomeIndex = 1;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
 pub enum EventStatus {
     Open,      
+    Proposed,
+    Disputed,
     Resolved,  
     Cancelled, 
+    Settling,
+    Settled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct NewOrder {
+    pub event_id: u64,
+    pub order_type: OrderType,
+    pub outcome: OutcomeIndex,
+    pub stake: Uint128,
+    pub odds: Decimal,
+    pub time_in_force: Option<TimeInForce>,
+}
+
+/// How long an order may rest on the book; defaults to `GoodTilCancelled`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeInForce {
+    GoodTilCancelled,
+    ImmediateOrCancel,
+    FillOrKill,
+    GoodTilDate { expires: Timestamp },
+    PostOnly,
+}
+
+/// Stake, exposure and volume limits; a `None` field is unlimited.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+pub struct RiskLimits {
+    pub min_stake: Option<Uint128>,
+    pub max_stake: Option<Uint128>,
+    pub max_liability_per_user: Option<Uint128>,
+    pub max_matched_volume: Option<Uint128>,
+}
+
+/// Odds in a band, up to and including `up_to`, move in steps of `tick` from where the previous
+/// band ended (1.0 for the first band).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct OddsBand {
+    pub up_to: Decimal,
+    pub tick: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum BetRole {
+    Backer,
+    Layer,
+    Any,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorScope {
+    Place,
+    Cancel,
+    Both,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
@@ -1,14 +1,32 @@
-use cosmwasm_std::{Addr, Coin, Decimal, Timestamp}; // Removed Uint128
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map, IndexedMap, MultiIndex, IndexList, Index};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::msg::{EventStatus, OrderStatus, OrderType, Outcome};
+use crate::msg::{EventStatus, OddsBand, OperatorScope, OrderStatus, OrderType, OutcomeIndex, PriceCondition, RiskLimits};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub admin: Addr,
     pub betting_denom: String, 
+    /// Commission in basis points charged on each address' net winnings per resolved event.
+    pub commission_bps: u16,
+    pub fee_collector: Addr,
+    /// Global circuit breaker: no orders can be placed or matched while set.
+    pub paused: bool,
+    /// How long a proposed resolution can be disputed before it can be finalized.
+    pub challenge_period_secs: u64,
+    /// Bond in `betting_denom` a disputer must post; slashed to the fees if the proposal stands.
+    pub dispute_bond: Uint128,
+    /// May rule on disputes alongside the admin.
+    pub arbitrator: Option<Addr>,
+    /// Contract answering `PriceFeedQueryMsg` for price-threshold markets.
+    pub price_oracle: Option<Addr>,
+    /// Global risk limits, overridable per event.
+    #[serde(default)]
+    pub limits: RiskLimits,
+    /// Prices orders may be placed at.
+    pub odds_ladder: Vec<OddsBand>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -18,9 +36,62 @@ pub struct Event {
     pub description: String,
     pub oracle: Addr,
     pub status: EventStatus,
-    pub winning_outcome: Option<Outcome>,
-    pub resolution_deadline: Option<Timestamp>, 
+    pub winning_outcome: Option<OutcomeIndex>,
+    /// No orders can be placed or matched from this time on.
+    pub betting_closes_at: Option<Timestamp>,
+    /// If the event is still unresolved at this time, anyone can void it.
+    pub resolve_by: Option<Timestamp>,
     pub creation_time: Timestamp,
+    /// Commission charged on the event's net winnings, fixed when the event is created.
+    pub commission_bps: u16,
+    /// Names of the event's runners; orders and resolution refer to them by index.
+    pub outcomes: Vec<String>,
+    /// Per-event circuit breaker: trading is halted but orders can still be cancelled.
+    pub suspended: bool,
+    /// Last position owner paid out by `SettleEvent`.
+    pub settlement_cursor: Option<Addr>,
+    /// Outcome put forward by the oracle while the event is `Proposed` or `Disputed`.
+    pub proposal: Option<ResolutionProposal>,
+    /// Oracles voting on the outcome, led by `oracle`. Empty on events from before quorum
+    /// resolution, where `oracle` alone decides.
+    pub oracles: Vec<Addr>,
+    /// Matching votes needed before an outcome is proposed.
+    pub oracle_threshold: u32,
+    pub oracle_votes: Vec<OracleVote>,
+    /// Set when `oracle` is a resolver contract: the market it reports `EventOutcome` for.
+    pub resolver_market_id: Option<String>,
+    /// Set on "will the price be above X at time T" markets, whose outcome is derived from the
+    /// configured price oracle instead of voted on.
+    pub price_condition: Option<PriceCondition>,
+    /// Overrides of the global risk limits, field by field.
+    #[serde(default)]
+    pub limits: RiskLimits,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OracleVote {
+    pub oracle: Addr,
+    pub outcome: OutcomeIndex,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolutionProposal {
+    pub outcome: OutcomeIndex,
+    pub challenge_ends_at: Timestamp,
+    pub disputer: Option<Addr>,
+    pub bond: Uint128,
+}
+
+pub fn default_outcomes() -> Vec<String> {
+    vec!["Yes".to_string(), "No".to_string()]
+}
+
+/// Betfair-style increments from 1.01 up to 1000, in percent.
+pub fn default_odds_ladder() -> Vec<OddsBand> {
+    [(200, 1), (300, 2), (400, 5), (600, 10), (1_000, 20), (2_000, 50), (3_000, 100), (5_000, 200), (10_000, 500), (100_000, 1_000)]
+        .into_iter()
+        .map(|(up_to, tick)| OddsBand { up_to: Decimal::percent(up_to), tick: Decimal::percent(tick) })
+        .collect()
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,12 +100,17 @@ pub struct Order {
     pub event_id: u64,
     pub owner: Addr,
     pub order_type: OrderType, 
-    pub outcome: Outcome,      
+    pub outcome: OutcomeIndex,      
     pub initial_backer_stake: Coin, 
     pub remaining_backer_stake: Coin, 
     pub odds: Decimal, 
+    /// Funds still held for the unmatched stake: the stake itself for a back, the liability for a
+    /// lay. Fills take whatever exceeds what the rest of the order needs, so nothing is lost to rounding.
+    pub remaining_escrow: Uint128,
     pub creation_time: Timestamp,
     pub status: OrderStatus,
+    /// Set on good-til-date orders: they stop matching at this time and anyone can cancel them.
+    pub expires_at: Option<Timestamp>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -45,39 +121,51 @@ pub struct MatchedBet {
     pub lay_addr: Addr,
     pub backer_stake: Coin, 
     pub layer_liability: Coin, 
-    pub outcome_backed: Outcome, 
+    pub outcome_backed: OutcomeIndex, 
     pub odds: Decimal, 
     pub creation_time: Timestamp,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Admin proposed by the current admin; becomes admin once it sends `AcceptAdmin`.
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending_admin");
+/// Commission collected on claims and not yet withdrawn to the fee collector.
+pub const ACCRUED_FEES: Item<Uint128> = Item::new("accrued_fees");
+/// Funds held for open orders and for matched bets not yet paid out, across all events.
+pub const TOTAL_ESCROW: Item<Uint128> = Item::new("total_escrow");
+/// Sum of all owners' `DEPOSITS`.
+pub const TOTAL_DEPOSITS: Item<Uint128> = Item::new("total_deposits");
+/// Dispute bonds posted on events whose dispute has not been ruled on yet.
+pub const TOTAL_BONDS: Item<Uint128> = Item::new("total_bonds");
 pub const NEXT_EVENT_ID: Item<u64> = Item::new("next_event_id");
 pub const NEXT_ORDER_ID: Item<u64> = Item::new("next_order_id");
 pub const NEXT_BET_ID: Item<u64> = Item::new("next_bet_id");
 pub const EVENTS: Map<u64, Event> = Map::new("events");
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorGrant {
+    pub scope: OperatorScope,
+    pub expires: Option<Timestamp>,
+}
+
+/// Operators an owner allows to act on its orders, keyed by `(owner, operator)`.
+pub const OPERATORS: Map<(&Addr, &Addr), OperatorGrant> = Map::new("operators");
+/// Funds an owner has deposited for operators to place orders with.
+pub const DEPOSITS: Map<&Addr, Uint128> = Map::new("deposits");
+
 pub struct OrderIndexes<'a> {
-    pub event_outcome_params: MultiIndex<'a, (u64, u8), Order, u64>,
     pub owner: MultiIndex<'a, Addr, Order, u64>,
 }
 
 impl<'a> IndexList<Order> for OrderIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Order>> + '_> {
-        let v: Vec<&dyn Index<Order>> = vec![&self.event_outcome_params, &self.owner];
+        let v: Vec<&dyn Index<Order>> = vec![&self.owner];
         Box::new(v.into_iter())
     }
 }
 
 pub fn orders<'a>() -> IndexedMap<u64, Order, OrderIndexes<'a>> {
     let indexes = OrderIndexes {
-        event_outcome_params: MultiIndex::new(
-            |_pk: &[u8], o: &Order| { 
-                let outcome_u8 = match o.outcome { Outcome::Yes => 0, Outcome::No => 1 };
-                (o.event_id, outcome_u8)
-            },
-            "orders", 
-            "orders__event_outcome_params",
-        ),
         owner: MultiIndex::new(
             |_pk: &[u8], o: &Order| o.owner.clone(),
             "orders",
@@ -87,5 +175,232 @@ pub fn orders<'a>() -> IndexedMap<u64, Order, OrderIndexes<'a>> {
     IndexedMap::new("orders", indexes)
 }
 
-pub const MATCHED_BETS: Map<u64, MatchedBet> = Map::new("matched_bets");
-pub const EVENT_TO_MATCHED_BETS: Map<u64, Vec<u64>> = Map::new("event_to_matched_bets");
\ No newline at end of file
+pub fn order_type_key(order_type: OrderType) -> u8 {
+    match order_type { OrderType::Back => 0, OrderType::Lay => 1 }
+}
+
+/// Price component of an `ORDER_BOOK` key. Ascending key order is best price first for the
+/// side that consumes it: incoming lays want the lowest back odds, incoming backs the highest lay odds.
+pub fn book_price_key(order_type: OrderType, odds: Decimal) -> u128 {
+    match order_type {
+        OrderType::Back => odds.atomics().u128(),
+        OrderType::Lay => u128::MAX - odds.atomics().u128(),
+    }
+}
+
+pub type BookKey = ((u64, u8, u8), (u128, u64));
+
+pub fn book_key(order: &Order) -> BookKey {
+    (
+        (order.event_id, order.outcome, order_type_key(order.order_type)),
+        (book_price_key(order.order_type, order.odds), order.id),
+    )
+}
+
+/// Live (Open / PartiallyFilled) orders only, keyed by (event, outcome, side) then (price, order id),
+/// so an ascending range over a side yields price-time priority. Value is the order owner.
+pub const ORDER_BOOK: Map<BookKey, Addr> = Map::new("order_book");
+
+pub struct MatchedBetIndexes<'a> {
+    pub event: MultiIndex<'a, u64, MatchedBet, u64>,
+    pub backer: MultiIndex<'a, Addr, MatchedBet, u64>,
+    pub layer: MultiIndex<'a, Addr, MatchedBet, u64>,
+}
+
+impl<'a> IndexList<MatchedBet> for MatchedBetIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<MatchedBet>> + '_> {
+        let v: Vec<&dyn Index<MatchedBet>> = vec![&self.event, &self.backer, &self.layer];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn matched_bets<'a>() -> IndexedMap<u64, MatchedBet, MatchedBetIndexes<'a>> {
+    let indexes = MatchedBetIndexes {
+        event: MultiIndex::new(
+            |_pk: &[u8], b: &MatchedBet| b.event_id,
+            "matched_bets",
+            "matched_bets__event",
+        ),
+        backer: MultiIndex::new(
+            |_pk: &[u8], b: &MatchedBet| b.backer_addr.clone(),
+            "matched_bets",
+            "matched_bets__backer",
+        ),
+        layer: MultiIndex::new(
+            |_pk: &[u8], b: &MatchedBet| b.lay_addr.clone(),
+            "matched_bets",
+            "matched_bets__layer",
+        ),
+    };
+    IndexedMap::new("matched_bets", indexes)
+}
+
+/// Bet ids per event, from before `matched_bets()` had an event index. Only `migrate` reads it,
+/// to index the bets it lists and then drop it.
+pub const EVENT_TO_MATCHED_BETS: Map<u64, Vec<u64>> = Map::new("event_to_matched_bets");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MarketStats {
+    pub last_traded_odds: Option<Decimal>,
+    /// Sum of backer stakes matched on the outcome.
+    pub matched_volume: Uint128,
+}
+
+/// Trading summary per (event id, outcome), updated on every match.
+pub const MARKET_STATS: Map<(u64, u8), MarketStats> = Map::new("market_stats");
+/// Per-address ledger for one event, kept up to date as orders are placed, matched and cancelled,
+/// so that settlement is a single lookup instead of a walk over every bet and order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Position {
+    pub event_id: u64,
+    pub owner: Addr,
+    /// Escrow still held for this address' unmatched orders on the event.
+    pub open_escrow: Uint128,
+    /// Sum of backer stakes this address has in matched bets.
+    pub matched_backer_stake: Uint128,
+    /// Sum of layer liabilities this address has in matched bets.
+    pub matched_layer_liability: Uint128,
+    /// Amount owed from matched bets if the outcome at this index wins.
+    pub payout_by_outcome: Vec<Uint128>,
+    pub claimed: bool,
+}
+
+impl Position {
+    pub fn new(event_id: u64, owner: Addr, outcome_count: usize) -> Self {
+        Position {
+            event_id,
+            owner,
+            open_escrow: Uint128::zero(),
+            matched_backer_stake: Uint128::zero(),
+            matched_layer_liability: Uint128::zero(),
+            payout_by_outcome: vec![Uint128::zero(); outcome_count],
+            claimed: false,
+        }
+    }
+}
+
+pub struct PositionIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, Position, (u64, Addr)>,
+}
+
+impl<'a> IndexList<Position> for PositionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Position>> + '_> {
+        let v: Vec<&dyn Index<Position>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn positions<'a>() -> IndexedMap<(u64, Addr), Position, PositionIndexes<'a>> {
+    let indexes = PositionIndexes {
+        owner: MultiIndex::new(
+            |_pk: &[u8], p: &Position| p.owner.clone(),
+            "positions",
+            "positions__owner",
+        ),
+    };
+    IndexedMap::new("positions", indexes)
+}
+
+/// Storage layout of v0.1.0, read once by `migrate` to upgrade a deployment in place.
+pub mod v0_1 {
+    use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
+    use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use crate::msg::{EventStatus, OrderStatus, OrderType, OutcomeIndex, OUTCOME_NO, OUTCOME_YES};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Config {
+        pub admin: Addr,
+        pub betting_denom: String,
+    }
+
+    /// Binary outcome, stored by name before outcomes became indexes.
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Copy)]
+    pub enum Outcome {
+        Yes,
+        No,
+    }
+
+    impl From<Outcome> for OutcomeIndex {
+        fn from(outcome: Outcome) -> Self {
+            match outcome {
+                Outcome::Yes => OUTCOME_YES,
+                Outcome::No => OUTCOME_NO,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Event {
+        pub id: u64,
+        pub creator: Addr,
+        pub description: String,
+        pub oracle: Addr,
+        pub status: EventStatus,
+        pub winning_outcome: Option<Outcome>,
+        pub resolution_deadline: Option<Timestamp>,
+        pub creation_time: Timestamp,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Order {
+        pub id: u64,
+        pub event_id: u64,
+        pub owner: Addr,
+        pub order_type: OrderType,
+        pub outcome: Outcome,
+        pub initial_backer_stake: Coin,
+        pub remaining_backer_stake: Coin,
+        pub odds: Decimal,
+        pub creation_time: Timestamp,
+        pub status: OrderStatus,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct MatchedBet {
+        pub id: u64,
+        pub event_id: u64,
+        pub backer_addr: Addr,
+        pub lay_addr: Addr,
+        pub backer_stake: Coin,
+        pub layer_liability: Coin,
+        pub outcome_backed: Outcome,
+        pub odds: Decimal,
+        pub creation_time: Timestamp,
+    }
+
+    pub const CONFIG: Item<Config> = Item::new("config");
+    pub const EVENTS: Map<u64, Event> = Map::new("events");
+    pub const MATCHED_BETS: Map<u64, MatchedBet> = Map::new("matched_bets");
+
+    pub struct OrderIndexes<'a> {
+        pub event_outcome_params: MultiIndex<'a, (u64, u8), Order, u64>,
+        pub owner: MultiIndex<'a, Addr, Order, u64>,
+    }
+
+    impl<'a> IndexList<Order> for OrderIndexes<'a> {
+        fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Order>> + '_> {
+            let v: Vec<&dyn Index<Order>> = vec![&self.event_outcome_params, &self.owner];
+            Box::new(v.into_iter())
+        }
+    }
+
+    /// Orders as v0.1.0 indexed them. Its owner index shares its namespace with the current one.
+    pub fn orders<'a>() -> IndexedMap<u64, Order, OrderIndexes<'a>> {
+        let indexes = OrderIndexes {
+            event_outcome_params: MultiIndex::new(
+                |_pk: &[u8], o: &Order| (o.event_id, OutcomeIndex::from(o.outcome)),
+                "orders",
+                "orders__event_outcome_params",
+            ),
+            owner: MultiIndex::new(
+                |_pk: &[u8], o: &Order| o.owner.clone(),
+                "orders",
+                "orders__owner",
+            ),
+        };
+        IndexedMap::new("orders", indexes)
+    }
+}
@@ -1,15 +1,22 @@
 // src/tests.rs
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::contract::{execute, instantiate, query, migrate};
     use crate::msg::{
-        InstantiateMsg, ExecuteMsg, QueryMsg, Outcome, OrderType,
-        ConfigResponse, EventResponse,
-        OrderResponse,
+        InstantiateMsg, ExecuteMsg, MigrateMsg, QueryMsg, OrderType, OutcomeIndex, OUTCOME_YES, OUTCOME_NO,
+        PriceCondition, PriceComparator, OperatorScope, OperatorResponse, DepositResponse, NewOrder, TimeInForce,
+        OrderBookResponse, PriceLevel, BetRole, UserPositionResponse, OrdersResponse, RiskLimits, OddsBand, OddsLadderResponse, EscrowInvariantResponse,
+        ConfigResponse, EventResponse, FeesResponse, OracleVotesResponse,
+        OrderResponse, MatchedBetsResponse, UnclaimedResponse,
     };
-    use crate::msg::OrderStatus;
+    use crate::msg::{EventStatus, OrderStatus};
+    use crate::mocks::price_feed as mock_price_feed;
+    use crate::mocks::resolver as mock_resolver;
+    use crate::state::{v0_1, EVENT_TO_MATCHED_BETS};
+    use crate::ContractError;
 
-    use cosmwasm_std::{coins, Addr, Decimal, Timestamp, Uint128, Api as StdApi}; 
+    use cosmwasm_std::{coins, Addr, Coin, Decimal, Empty, Int128, Order as CwOrder, Timestamp, Uint128}; 
     use cw_multi_test::{App, ContractWrapper, Executor, AppResponse, BasicAppBuilder};
     use anyhow;
 
@@ -18,6 +25,7 @@ mod tests {
     const USER2_ID_STR: &str = "user0002";
     const USER3_ID_STR: &str = "user0003";
     const ORACLE_ID_STR: &str = "oracle0001";
+    const FEE_COLLECTOR_ID_STR: &str = "fees0001";
     const BETTING_DENOM: &str = "uinj";
 
     fn decimal_times_uint128_trunc_for_test(decimal_val: Decimal, uint128_val: Uint128) -> Uint128 {
@@ -34,6 +42,10 @@ mod tests {
 
     // setup_contract now also returns the generated admin Addr for convenience
     fn setup_contract(app: &mut App, betting_denom: String) -> (Addr, Addr) {
+        setup_contract_with_commission(app, betting_denom, None, None)
+    }
+
+    fn setup_contract_with_commission(app: &mut App, betting_denom: String, commission_bps: Option<u16>, fee_collector: Option<String>) -> (Addr, Addr) {
         let contract_wrapper = ContractWrapper::new(execute, instantiate, query).with_migrate(migrate);
         let code_id = app.store_code(Box::new(contract_wrapper));
 
@@ -42,6 +54,12 @@ mod tests {
         let instantiate_msg = InstantiateMsg {
             admin: Some(admin_addr.to_string()), 
             betting_denom,
+            commission_bps,
+            fee_collector,
+            challenge_period_secs: None,
+            dispute_bond: None,
+            arbitrator: None,
+            price_oracle: None,
         };
         
         let contract_addr = app.instantiate_contract(
@@ -56,9 +74,71 @@ mod tests {
         (contract_addr, admin_addr)
     }
 
+    /// Proposes `winning_outcome` as the oracle and finalizes it once the challenge period is over.
+    fn resolve_event(app: &mut App, contract_addr: &Addr, oracle_addr: &Addr, event_id: u64, winning_outcome: OutcomeIndex) -> anyhow::Result<AppResponse> {
+        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id, winning_outcome }, &[])?;
+        let config: ConfigResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {})?;
+        app.update_block(|block| block.time = block.time.plus_seconds(config.challenge_period_secs));
+        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id }, &[])
+    }
+
+    /// `ExecuteMsg::CreateEvent` as a struct, so a test spells out only the fields it sets.
+    #[derive(Default)]
+    struct CreateEventMsg {
+        description: String,
+        oracle_addr: Option<String>,
+        oracles: Option<Vec<String>>,
+        oracle_threshold: Option<u32>,
+        resolver_market_id: Option<String>,
+        price_condition: Option<PriceCondition>,
+        limits: Option<RiskLimits>,
+        betting_closes_at: Option<Timestamp>,
+        resolve_by: Option<Timestamp>,
+        outcomes: Option<Vec<String>>,
+    }
+
+    impl From<CreateEventMsg> for ExecuteMsg {
+        fn from(msg: CreateEventMsg) -> Self {
+            let CreateEventMsg { description, oracle_addr, oracles, oracle_threshold, resolver_market_id, price_condition, limits, betting_closes_at, resolve_by, outcomes } = msg;
+            ExecuteMsg::CreateEvent { description, oracle_addr, oracles, oracle_threshold, resolver_market_id, price_condition, limits, betting_closes_at, resolve_by, outcomes }
+        }
+    }
+
+    /// A binary event resolved by `oracle`, or by its creator when `None`, with nothing else set.
+    fn create_event_msg(description: &str, oracle: Option<&Addr>) -> CreateEventMsg {
+        CreateEventMsg { description: description.to_string(), oracle_addr: oracle.map(Addr::to_string), ..CreateEventMsg::default() }
+    }
+
+    /// `ExecuteMsg::UpdateConfig` as a struct; the default changes nothing.
+    #[derive(Default)]
+    struct UpdateConfigMsg {
+        commission_bps: Option<u16>,
+        fee_collector: Option<String>,
+        challenge_period_secs: Option<u64>,
+        dispute_bond: Option<Uint128>,
+        arbitrator: Option<String>,
+        price_oracle: Option<String>,
+        limits: Option<RiskLimits>,
+        odds_ladder: Option<Vec<OddsBand>>,
+    }
+
+    impl From<UpdateConfigMsg> for ExecuteMsg {
+        fn from(msg: UpdateConfigMsg) -> Self {
+            let UpdateConfigMsg { commission_bps, fee_collector, challenge_period_secs, dispute_bond, arbitrator, price_oracle, limits, odds_ladder } = msg;
+            ExecuteMsg::UpdateConfig { commission_bps, fee_collector, challenge_period_secs, dispute_bond, arbitrator, price_oracle, limits, odds_ladder }
+        }
+    }
+
+    /// Queries the escrow invariant and asserts the balance covers it.
+    fn escrow_invariant(app: &App, contract_addr: &Addr) -> EscrowInvariantResponse {
+        let invariant: EscrowInvariantResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::CheckEscrowInvariant {}).unwrap();
+        assert!(invariant.holds);
+        invariant
+    }
+
     fn default_app() -> App {
         // Create a new App and initialize balances with bech32 addresses
-        let mut app = BasicAppBuilder::new_custom().build(|router, api, storage| {
+        BasicAppBuilder::new_custom().build(|router, api, storage| {
             let user1_addr = api.addr_make(USER1_ID_STR);
             let user2_addr = api.addr_make(USER2_ID_STR);
             let user3_addr = api.addr_make(USER3_ID_STR);
@@ -70,8 +150,7 @@ mod tests {
             router.bank.init_balance(storage, &user3_addr, coins(1_000_000_000, BETTING_DENOM)).unwrap();
             router.bank.init_balance(storage, &oracle_addr, coins(100_000, BETTING_DENOM)).unwrap();
             router.bank.init_balance(storage, &admin_addr, coins(100_000, BETTING_DENOM)).unwrap();
-        });
-        app
+        })
     }
 
     #[test]
@@ -84,6 +163,8 @@ mod tests {
         assert_eq!(config_res.next_event_id, 0);
         assert_eq!(config_res.next_order_id, 0);
         assert_eq!(config_res.next_bet_id, 0);
+        assert_eq!(config_res.commission_bps, 0);
+        assert_eq!(config_res.fee_collector, admin_addr);
     }
 
     #[test]
@@ -92,16 +173,17 @@ mod tests {
         let (contract_addr, _) = setup_contract(&mut app, BETTING_DENOM.to_string());
         
         let user1_addr = app.api().addr_make(USER1_ID_STR);
-        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Will it rain tomorrow?".to_string(), oracle_addr: None, resolution_deadline: Some(Timestamp::from_seconds(app.block_info().time.seconds() + 10000))}, &[]).unwrap();
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { betting_closes_at: Some(Timestamp::from_seconds(app.block_info().time.seconds() + 10000)), ..create_event_msg("Will it rain tomorrow?", None) }), &[]).unwrap();
         let event_id_1: u64 = res.custom_attrs(1).iter().find(|attr| attr.key == "event_id").unwrap().value.parse().unwrap();
         assert_eq!(event_id_1, 1);
         let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
         assert_eq!(event_res.event.id, 1);
         assert_eq!(event_res.event.oracle, user1_addr);
+        assert_eq!(event_res.event.outcomes, vec!["Yes".to_string(), "No".to_string()]);
 
         let user2_addr = app.api().addr_make(USER2_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
-        let res2 = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Price of ATOM > $10 by EOY?".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None}, &[]).unwrap();
+        let res2 = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Price of ATOM > $10 by EOY?", Some(&oracle_addr))), &[]).unwrap();
         let event_id_2: u64 = res2.custom_attrs(1).iter().find(|attr| attr.key == "event_id").unwrap().value.parse().unwrap();
         assert_eq!(event_id_2, 2);
         let event_res_2: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 2 }).unwrap();
@@ -115,10 +197,10 @@ mod tests {
         let user1_addr = app.api().addr_make(USER1_ID_STR);
         let user2_addr = app.api().addr_make(USER2_ID_STR);
 
-        app.execute_contract(user1_addr.clone(),contract_addr.clone(),&ExecuteMsg::CreateEvent { description: "Test Event 1".to_string(), oracle_addr: None, resolution_deadline: None },&[],).unwrap();
+        app.execute_contract(user1_addr.clone(),contract_addr.clone(),&ExecuteMsg::from(create_event_msg("Test Event 1", None)),&[],).unwrap();
         let stake_amount = Uint128::new(100_000);
         let odds = Decimal::from_atomics(Uint128::new(250), 2).unwrap();
-        let res = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake: stake_amount, odds}, &coins(stake_amount.u128(), BETTING_DENOM)).unwrap();
+        let res = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: stake_amount, odds, time_in_force: None, owner: None }, &coins(stake_amount.u128(), BETTING_DENOM)).unwrap();
         let order_id: u64 = res.custom_attrs(1).iter().find(|attr| attr.key == "order_id").unwrap().value.parse().unwrap();
         assert_eq!(order_id, 1);
         let order_res: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
@@ -134,12 +216,12 @@ mod tests {
         let user1_addr = app.api().addr_make(USER1_ID_STR);
         let user3_addr = app.api().addr_make(USER3_ID_STR);
 
-        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Test".to_string(), oracle_addr: None, resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Test", None)), &[]).unwrap();
         let backer_stake_to_match = Uint128::new(50_000);
         let odds = Decimal::from_atomics(Uint128::new(200), 2).unwrap();
         let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
         let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, backer_stake_to_match);
-        let res = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::Yes, stake: backer_stake_to_match, odds}, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
+        let res = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: backer_stake_to_match, odds, time_in_force: None, owner: None }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
         let order_id: u64 = res.custom_attrs(1).iter().find(|attr| attr.key == "order_id").unwrap().value.parse().unwrap();
         assert_eq!(order_id, 1);
         let order_res: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
@@ -157,15 +239,15 @@ mod tests {
         let user2_addr = app.api().addr_make(USER2_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
 
-        app.execute_contract( user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Event X".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract( user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Event X", Some(&oracle_addr))), &[]).unwrap();
         let back_stake = Uint128::new(100_000);
         let odds = Decimal::from_atomics(Uint128::new(300), 2).unwrap();
-        app.execute_contract(user1_addr.clone(),contract_addr.clone(),&ExecuteMsg::PlaceOrder {event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake: back_stake, odds,},&coins(back_stake.u128(), BETTING_DENOM),).unwrap();
+        app.execute_contract(user1_addr.clone(),contract_addr.clone(),&ExecuteMsg::PlaceOrder {event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None },&coins(back_stake.u128(), BETTING_DENOM),).unwrap();
         let lay_backer_stake_to_match = back_stake;
         let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
         let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, lay_backer_stake_to_match);
-        let res_lay = app.execute_contract(user2_addr.clone(),contract_addr.clone(),&ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::Yes, stake: lay_backer_stake_to_match, odds,},&coins(layer_liability.u128(), BETTING_DENOM),).unwrap();
-        assert!(res_lay.custom_attrs(1).into_iter().any(|attr| attr.key == "matched_bet_id"));
+        let res_lay = app.execute_contract(user2_addr.clone(),contract_addr.clone(),&ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: lay_backer_stake_to_match, odds, time_in_force: None, owner: None },&coins(layer_liability.u128(), BETTING_DENOM),).unwrap();
+        assert!(res_lay.custom_attrs(1).iter().any(|attr| attr.key == "matched_bet_id"));
         let order1_res: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
         let order2_res: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 2 }).unwrap();
         assert_eq!(order1_res.order.status, OrderStatus::Filled);
@@ -183,14 +265,14 @@ mod tests {
         let user2_addr = app.api().addr_make(USER2_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
 
-        app.execute_contract( admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Event Partial".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract( admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Event Partial", Some(&oracle_addr))), &[]).unwrap();
         let odds = Decimal::from_atomics(Uint128::new(200), 2).unwrap();
         let user1_back_stake = Uint128::new(100_000);
-        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::No, stake: user1_back_stake, odds, }, &coins(user1_back_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_NO, stake: user1_back_stake, odds, time_in_force: None, owner: None }, &coins(user1_back_stake.u128(), BETTING_DENOM)).unwrap();
         let user2_lay_backer_stake_to_match = Uint128::new(50_000);
         let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
         let user2_layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, user2_lay_backer_stake_to_match);
-        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::No, stake: user2_lay_backer_stake_to_match, odds, }, &coins(user2_layer_liability.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_NO, stake: user2_lay_backer_stake_to_match, odds, time_in_force: None, owner: None }, &coins(user2_layer_liability.u128(), BETTING_DENOM)).unwrap();
         let order1: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
         let order2: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 2 }).unwrap();
         assert_eq!(order1.order.status, OrderStatus::PartiallyFilled);
@@ -199,6 +281,105 @@ mod tests {
         assert_eq!(order2.order.remaining_backer_stake.amount, Uint128::zero());
     }
 
+    #[test]
+    fn back_order_matches_best_lay_price_then_oldest() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Price Priority", None)), &[]).unwrap();
+        let lay_stake = Uint128::new(40_000);
+        // Order 1: lay at 2.20, order 2: lay at 2.50, order 3: lay at 2.50 (younger), order 4: lay at 1.90 (not acceptable).
+        for (layer, odds) in [(&user2_addr, Decimal::percent(220)), (&user2_addr, Decimal::percent(250)), (&user3_addr, Decimal::percent(250)), (&user3_addr, Decimal::percent(190))] {
+            let liability = decimal_times_uint128_trunc_for_test(odds - Decimal::one(), lay_stake);
+            app.execute_contract(layer.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: lay_stake, odds, time_in_force: None, owner: None }, &coins(liability.u128(), BETTING_DENOM)).unwrap();
+        }
+
+        let back_stake = Uint128::new(100_000);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
+
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.len(), 3);
+        assert_eq!(bets.matched_bets[0].odds, Decimal::percent(250));
+        assert_eq!(bets.matched_bets[0].lay_addr, user2_addr);
+        assert_eq!(bets.matched_bets[1].odds, Decimal::percent(250));
+        assert_eq!(bets.matched_bets[1].lay_addr, user3_addr);
+        assert_eq!(bets.matched_bets[2].odds, Decimal::percent(220));
+        assert_eq!(bets.matched_bets[2].backer_stake.amount, Uint128::new(20_000));
+
+        let order1: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!(order1.order.status, OrderStatus::PartiallyFilled);
+        let order4: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!(order4.order.status, OrderStatus::Open);
+        let order5: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 5 }).unwrap();
+        assert_eq!(order5.order.status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn lay_order_fills_at_resting_back_price_and_refunds_excess_liability() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Price Improvement", None)), &[]).unwrap();
+        let stake = Uint128::new(100_000);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let lay_odds = Decimal::percent(300);
+        let escrowed = decimal_times_uint128_trunc_for_test(lay_odds - Decimal::one(), stake);
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds: lay_odds, time_in_force: None, owner: None }, &coins(escrowed.u128(), BETTING_DENOM)).unwrap();
+
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.len(), 1);
+        assert_eq!(bets.matched_bets[0].odds, Decimal::percent(200));
+        assert_eq!(bets.matched_bets[0].layer_liability.amount, stake);
+
+        let user2_bal_after = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        assert_eq!(user2_bal_after, user2_bal_before - stake);
+        let contract_balance = app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap();
+        assert_eq!(contract_balance.amount, stake + stake);
+    }
+
+    #[test]
+    fn cancelled_and_filled_orders_leave_the_book() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Book Hygiene", None)), &[]).unwrap();
+        let stake = Uint128::new(50_000);
+        let odds = Decimal::percent(200);
+        // Order 1 is cancelled, order 2 is filled by order 3; order 4 must not match either of them.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.len(), 1);
+        assert_eq!(bets.matched_bets[0].lay_addr, user3_addr);
+        let order4: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!(order4.order.status, OrderStatus::Open);
+
+        // Only live orders are listed, in id order across outcomes and sides.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_NO, stake, odds: Decimal::percent(300), time_in_force: None, owner: None }, &coins(2 * stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds: Decimal::percent(250), time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        let list = |start_after: Option<u64>, limit: Option<u32>, filter_outcome: Option<OutcomeIndex>| -> Vec<u64> {
+            let res: OrdersResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListOrdersByEvent { event_id: 1, start_after, limit, filter_order_type: None, filter_outcome }).unwrap();
+            res.orders.iter().map(|order| order.id).collect()
+        };
+        assert_eq!(list(None, None, None), vec![4, 5, 6]);
+        assert_eq!(list(Some(4), Some(1), None), vec![5]);
+        assert_eq!(list(None, None, Some(OUTCOME_YES)), vec![4, 6]);
+    }
+
     #[test]
     fn cancel_open_order() { 
         let mut app = default_app();
@@ -206,9 +387,9 @@ mod tests {
         let admin_addr = app.api().addr_make(ADMIN_ID_STR);
         let user1_addr = app.api().addr_make(USER1_ID_STR);
 
-        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Cancel Event".to_string(), oracle_addr: None, resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Cancel Event", None)), &[]).unwrap();
         let stake = Uint128::new(70_000);
-        app.execute_contract( user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake, odds: Decimal::percent(200) }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract( user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
         
         let balance_before_cancel = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
         
@@ -230,21 +411,30 @@ mod tests {
         let user2_addr = app.api().addr_make(USER2_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
 
-        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Resolve Backer Win".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Resolve Backer Win", Some(&oracle_addr))), &[]).unwrap();
         let back_stake = Uint128::new(100_000);
         let odds = Decimal::from_atomics(Uint128::new(200), 2).unwrap();
         let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
         let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, back_stake);
 
-        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake: back_stake, odds }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
-        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::Yes, stake: back_stake, odds }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
         
         let user1_bal_before_resolve = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
         
-        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveEvent { event_id: 1, winning_outcome: Outcome::Yes }, &[]).unwrap();
-
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        // Resolution only records the outcome; winnings are pulled by the winner.
         let user1_bal_after_resolve = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
-        assert_eq!(user1_bal_after_resolve, user1_bal_before_resolve + back_stake + layer_liability);
+        assert_eq!(user1_bal_after_resolve, user1_bal_before_resolve);
+
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        let user1_bal_after_claim = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        assert_eq!(user1_bal_after_claim, user1_bal_before_resolve + back_stake + layer_liability);
+
+        let err = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToClaim { event_id: 1 });
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::AlreadyClaimed { event_id: 1 });
     }
     
     #[test]
@@ -256,23 +446,143 @@ mod tests {
         let user2_addr = app.api().addr_make(USER2_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
 
-        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Resolve Layer Win".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Resolve Layer Win", Some(&oracle_addr))), &[]).unwrap();
         let back_stake = Uint128::new(100_000);
         let odds = Decimal::from_atomics(Uint128::new(200), 2).unwrap();
         let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
         let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, back_stake);
 
-        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake: back_stake, odds }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
-        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::Yes, stake: back_stake, odds }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
         
         let user2_bal_before_resolve = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
 
-        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveEvent { event_id: 1, winning_outcome: Outcome::No }, &[]).unwrap();
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_NO).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
 
         let user2_bal_after_resolve = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
         assert_eq!(user2_bal_after_resolve, user2_bal_before_resolve + back_stake + layer_liability);
     }
 
+    #[test]
+    fn resolve_event_backer_wins_with_commission() { 
+        let mut app = default_app();
+        let fee_collector_addr = app.api().addr_make(FEE_COLLECTOR_ID_STR);
+        let (contract_addr, admin_addr) = setup_contract_with_commission(&mut app, BETTING_DENOM.to_string(), Some(500), Some(fee_collector_addr.to_string()));
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Resolve Backer Win With Fee", Some(&oracle_addr))), &[]).unwrap();
+        let back_stake = Uint128::new(100_000);
+        let odds = Decimal::from_atomics(Uint128::new(200), 2).unwrap();
+        let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
+        let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, back_stake);
+
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        // Raising the rate after the fact does not reach an event created under the old one.
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(2_000), ..UpdateConfigMsg::default() }), &[]).unwrap();
+
+        // 5% of the backer's net winnings (the layer's liability); the stake itself is not charged.
+        let expected_commission = layer_liability.multiply_ratio(500u128, 10_000u128);
+        let user1_bal_before_claim = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        let user1_bal_after_claim = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        assert_eq!(user1_bal_after_claim, user1_bal_before_claim + back_stake + layer_liability - expected_commission);
+
+        let fees: FeesResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetFees {}).unwrap();
+        assert_eq!(fees.accrued_fees.amount, expected_commission);
+        assert_eq!(fees.fee_collector, fee_collector_addr);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::WithdrawFees {}, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::WithdrawFees {}, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(fee_collector_addr.to_string(), BETTING_DENOM).unwrap().amount, expected_commission);
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+    }
+
+    #[test]
+    fn resolve_event_layer_wins_with_commission() { 
+        let mut app = default_app();
+        let fee_collector_addr = app.api().addr_make(FEE_COLLECTOR_ID_STR);
+        let (contract_addr, admin_addr) = setup_contract_with_commission(&mut app, BETTING_DENOM.to_string(), Some(200), Some(fee_collector_addr.to_string()));
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Resolve Layer Win With Fee", Some(&oracle_addr))), &[]).unwrap();
+        let back_stake = Uint128::new(100_000);
+        let odds = Decimal::from_atomics(Uint128::new(350), 2).unwrap();
+        let odds_factor = odds.checked_sub(Decimal::one()).unwrap();
+        let layer_liability = decimal_times_uint128_trunc_for_test(odds_factor, back_stake);
+
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(back_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: back_stake, odds, time_in_force: None, owner: None }, &coins(layer_liability.u128(), BETTING_DENOM)).unwrap();
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_NO).unwrap();
+
+        // The layer's net winnings are the backer's stake.
+        let expected_commission = back_stake.multiply_ratio(200u128, 10_000u128);
+        let user2_bal_before_claim = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        let user2_bal_after_claim = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        assert_eq!(user2_bal_after_claim, user2_bal_before_claim + back_stake + layer_liability - expected_commission);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToClaim { event_id: 1 });
+        let fees: FeesResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetFees {}).unwrap();
+        assert_eq!(fees.accrued_fees.amount, expected_commission);
+    }
+
+    #[test]
+    fn update_config_commission_is_admin_only_and_bounded() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let fee_collector_addr = app.api().addr_make(FEE_COLLECTOR_ID_STR);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(100), ..UpdateConfigMsg::default() }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(2_001), ..UpdateConfigMsg::default() }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidCommission { commission_bps: 2_001, max_bps: 2_000 });
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(300), fee_collector: Some(fee_collector_addr.to_string()), ..UpdateConfigMsg::default() }), &[]).unwrap();
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {}).unwrap();
+        assert_eq!(config_res.commission_bps, 300);
+        assert_eq!(config_res.fee_collector, fee_collector_addr);
+    }
+
+    #[test]
+    fn admin_transfer_requires_acceptance_by_proposed_admin() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::AcceptAdmin {}, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoPendingAdmin {});
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeNewAdmin { new_admin: user1_addr.to_string() }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeNewAdmin { new_admin: user1_addr.to_string() }, &[]).unwrap();
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {}).unwrap();
+        assert_eq!(config_res.admin, admin_addr);
+        assert_eq!(config_res.pending_admin, Some(user1_addr.clone()));
+
+        let err = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::AcceptAdmin {}, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::AcceptAdmin {}, &[]).unwrap();
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "previous_admin" && attr.value == admin_addr.to_string()));
+
+        let config_res: ConfigResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {}).unwrap();
+        assert_eq!(config_res.admin, user1_addr);
+        assert_eq!(config_res.pending_admin, None);
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(100), ..UpdateConfigMsg::default() }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { commission_bps: Some(100), ..UpdateConfigMsg::default() }), &[]).unwrap();
+    }
+
     #[test]
     fn resolve_event_with_open_orders_refund() { 
         let mut app = default_app();
@@ -284,7 +594,7 @@ mod tests {
         let user3_addr = app.api().addr_make(USER3_ID_STR);
         let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
 
-        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CreateEvent { description: "Resolve with Open Orders".to_string(), oracle_addr: Some(oracle_addr.to_string()), resolution_deadline: None }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Resolve with Open Orders", Some(&oracle_addr))), &[]).unwrap();
         
         let back_stake_matched = Uint128::new(100_000);
         let odds_matched = Decimal::percent(200);
@@ -292,23 +602,31 @@ mod tests {
         let liability_matched = decimal_times_uint128_trunc_for_test(odds_factor_matched, back_stake_matched);
         
         let user1_bal_before_placing_order = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
-        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::Yes, stake: back_stake_matched, odds: odds_matched }, &coins(back_stake_matched.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: back_stake_matched, odds: odds_matched, time_in_force: None, owner: None }, &coins(back_stake_matched.u128(), BETTING_DENOM)).unwrap();
         let user1_bal_after_placing_order = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
         assert_eq!(user1_bal_after_placing_order, user1_bal_before_placing_order - back_stake_matched);
 
         let user2_bal_before_placing_order = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
-        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: Outcome::Yes, stake: back_stake_matched, odds: odds_matched }, &coins(liability_matched.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: back_stake_matched, odds: odds_matched, time_in_force: None, owner: None }, &coins(liability_matched.u128(), BETTING_DENOM)).unwrap();
         let user2_bal_after_placing_order = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
         assert_eq!(user2_bal_after_placing_order, user2_bal_before_placing_order - liability_matched);
 
         let open_back_stake_user3 = Uint128::new(50_000);
-        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: Outcome::No, stake: open_back_stake_user3, odds: Decimal::percent(300) }, &coins(open_back_stake_user3.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_NO, stake: open_back_stake_user3, odds: Decimal::percent(300), time_in_force: None, owner: None }, &coins(open_back_stake_user3.u128(), BETTING_DENOM)).unwrap();
         
         let user1_bal_before_resolve = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
         let user3_bal_before_resolve = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
 
-        let res_resolve: Result<AppResponse, anyhow::Error> = app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveEvent { event_id: 1, winning_outcome: Outcome::Yes }, &[]);
+        let res_resolve = resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES);
         assert!(res_resolve.is_ok());
+
+        let unclaimed: UnclaimedResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListUnclaimed { addr: user3_addr.to_string(), start_after: None, limit: None }).unwrap();
+        assert_eq!(unclaimed.unclaimed.len(), 1);
+        assert_eq!(unclaimed.unclaimed[0].amount.amount, open_back_stake_user3);
+
+        let res_claim: Result<AppResponse, anyhow::Error> = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]);
+        assert!(res_claim.is_ok());
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
         
         let user1_bal_after_resolve = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
         let expected_user1_payout = back_stake_matched + liability_matched;
@@ -317,16 +635,1152 @@ mod tests {
         let user3_bal_after_resolve = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
         assert_eq!(user3_bal_after_resolve, user3_bal_before_resolve + open_back_stake_user3);
 
-        if let Ok(response) = res_resolve {
+        if let Ok(response) = res_claim {
             let wasm_event_attributes: Vec<_> = response.events.iter()
                 .filter(|event| event.ty == "wasm") 
                 .flat_map(|event| event.attributes.iter())
                 .collect();
             
-            // payout_winner attribute comes from Addr::to_string(), which will be the bech32 string
-            assert!(wasm_event_attributes.iter().any(|attr| attr.key == "payout_winner" && attr.value == user1_addr.to_string()));
+            // claimant attribute comes from Addr::to_string(), which will be the bech32 string
+            assert!(wasm_event_attributes.iter().any(|attr| attr.key == "claimant" && attr.value == user1_addr.to_string()));
             assert!(wasm_event_attributes.iter().any(|attr| attr.key == "payout_amount" && attr.value.starts_with(&expected_user1_payout.to_string())));
-            assert!(wasm_event_attributes.iter().any(|attr| attr.key == "refunded_open_order_id" && attr.value == "3"));
         }
+
+        let unclaimed: UnclaimedResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListUnclaimed { addr: user3_addr.to_string(), start_after: None, limit: None }).unwrap();
+        assert!(unclaimed.unclaimed.is_empty());
+    }
+
+    #[test]
+    fn cancel_event_refunds_matched_bets_and_open_orders() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Postponed Match", Some(&oracle_addr))), &[]).unwrap();
+        let stake = Uint128::new(100_000);
+        let odds = Decimal::percent(300);
+        let liability = decimal_times_uint128_trunc_for_test(odds - Decimal::one(), stake);
+
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user3_bal_before = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(liability.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_NO, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelEvent { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+
+        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelEvent { event_id: 1 }, &[]).unwrap();
+        for user in [&user1_addr, &user2_addr, &user3_addr] {
+            app.execute_contract(user.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        }
+
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before);
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before);
+        assert_eq!(app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount, user3_bal_before);
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Cancelled);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotOpen { event_id: 1 });
+        let err = app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: OUTCOME_YES }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotOpen { event_id: 1 });
+    }
+
+    #[test]
+    fn claim_rejected_while_event_open_and_cancel_order_rejected_after_resolve() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Claim Timing", Some(&oracle_addr))), &[]).unwrap();
+        let stake = Uint128::new(100_000);
+        let odds = Decimal::percent(250);
+        let liability = decimal_times_uint128_trunc_for_test(odds - Decimal::one(), stake);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: stake + stake, odds, time_in_force: None, owner: None }, &coins((stake + stake).u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(liability.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotSettled { event_id: 1 });
+
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_NO).unwrap();
+        // The unmatched half of order 1 is returned by the claim, not by cancelling the order.
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotOpen { event_id: 1 });
+
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + stake);
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before + stake + liability);
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+    }
+
+    #[test]
+    fn settle_event_pays_out_in_batches() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Crank", Some(&oracle_addr))), &[]).unwrap();
+        let stake = Uint128::new(100_000);
+        let odds = Decimal::percent(200);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_NO, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_NO, stake, odds: Decimal::percent(150), time_in_force: None, owner: None }, &coins(stake.u128() / 2, BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotSettled { event_id: 1 });
+
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user3_bal_before = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+
+        // A participant may still pull their own funds while the crank is running.
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+
+        let mut calls = 0;
+        loop {
+            calls += 1;
+            app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: Some(1) }, &[]).unwrap();
+            let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+            if event_res.event.status == EventStatus::Settled {
+                break;
+            }
+            assert_eq!(event_res.event.status, EventStatus::Settling);
+            assert!(calls < 10);
+        }
+        // Three positions, then two leftover orders, one per call.
+        assert_eq!(calls, 5);
+
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + stake + stake);
+        assert_eq!(app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount, user3_bal_before + stake + stake / Uint128::new(2));
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+        for order_id in [3, 4] {
+            let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id }).unwrap();
+            assert_eq!(order.order.status, OrderStatus::Cancelled);
+        }
+
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventAlreadySettled { event_id: 1 });
+    }
+
+    #[test]
+    fn multi_outcome_market_settles_per_runner() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { outcomes: Some(vec!["Solo".to_string()]), ..create_event_msg("One Runner", None) }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOutcomeCount { count: 1 });
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { outcomes: Some(vec!["Home".to_string(), "Home".to_string()]), ..create_event_msg("Twins", None) }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOutcomeName { name: "Home".to_string() });
+
+        let runners = vec!["Home".to_string(), "Draw".to_string(), "Away".to_string()];
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { outcomes: Some(runners.clone()), ..create_event_msg("Match Winner", Some(&oracle_addr)) }), &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.outcomes, runners);
+
+        let stake = Uint128::new(100_000);
+        let odds = Decimal::percent(300);
+        let liability = decimal_times_uint128_trunc_for_test(odds - Decimal::one(), stake);
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: 3, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOutcome { event_id: 1, outcome: 3 });
+
+        // user1 backs the draw against user2; user3 backs home against user2. Away wins, so both lays win.
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: 1, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: 1, stake, odds, time_in_force: None, owner: None }, &coins(liability.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: 0, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: 0, stake, odds, time_in_force: None, owner: None }, &coins(liability.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: 5 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOutcome { event_id: 1, outcome: 5 });
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, 2).unwrap();
+
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        let user2_bal_after = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        assert_eq!(user2_bal_after, user2_bal_before + (stake + liability) * Uint128::new(2));
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToClaim { event_id: 1 });
+    }
+
+    #[test]
+    fn pause_and_suspend_block_trading_but_not_cancellation() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Disputed Goal", Some(&oracle_addr))), &[]).unwrap();
+        let stake = Uint128::new(10_000);
+        let place = ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds: Decimal::percent(200), time_in_force: None, owner: None };
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::Pause {}, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::Pause {}, &[]).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ContractPaused {});
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::Unpause {}, &[]).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::SuspendEvent { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::SuspendEvent { event_id: 1 }, &[]).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventSuspended { event_id: 1 });
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 2 }, &[]).unwrap();
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResumeEvent { event_id: 1 }, &[]).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 3 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Open);
+    }
+
+    #[test]
+    fn betting_close_rejects_orders_and_refunds_unmatched_book() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let now = app.block_info().time;
+
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { betting_closes_at: Some(now.plus_seconds(1_000)), resolve_by: Some(now.plus_seconds(100)), ..create_event_msg("Kick-off", None) }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidSchedule {});
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { betting_closes_at: Some(now.plus_seconds(100)), resolve_by: Some(now.plus_seconds(1_000)), ..create_event_msg("Kick-off", None) }), &[]).unwrap();
+
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(200);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(4_000), odds, time_in_force: None, owner: None }, &coins(4_000, BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::RefundUnmatched { event_id: 1, limit: None }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::BettingStillOpen { event_id: 1 });
+
+        app.update_block(|block| block.time = block.time.plus_seconds(100));
+        let err = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::BettingClosed { event_id: 1 });
+
+        // Anyone can return the unmatched 6_000 of order 1 without waiting for the oracle.
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let res = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::RefundUnmatched { event_id: 1, limit: None }, &[]).unwrap();
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "book_drained" && attr.value == "true"));
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + Uint128::new(6_000));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Cancelled);
+        assert_eq!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount, Uint128::new(8_000));
+
+        // The matched part still settles normally and the refunded escrow is not paid twice.
+        resolve_event(&mut app, &contract_addr, &admin_addr, 1, OUTCOME_YES).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + Uint128::new(14_000));
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+    }
+
+    #[test]
+    fn unresolved_event_is_voided_by_anyone_after_resolve_by() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        let now = app.block_info().time;
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { resolve_by: Some(now.plus_seconds(1_000)), ..create_event_msg("Absent Oracle", Some(&oracle_addr)) }), &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.betting_closes_at, Some(now.plus_seconds(1_000)));
+
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(300);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(20_000, BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::VoidExpiredEvent { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ResolveByNotReached { event_id: 1 });
+
+        app.update_block(|block| block.time = block.time.plus_seconds(1_000));
+        let err = app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: OUTCOME_YES }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ResolveByPassed { event_id: 1 });
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::VoidExpiredEvent { event_id: 1 }, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Cancelled);
+
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + stake);
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before + Uint128::new(20_000));
+    }
+
+    #[test]
+    fn proposed_resolution_finalizes_only_after_challenge_period() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Two Phase", Some(&oracle_addr))), &[]).unwrap();
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(200);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: OUTCOME_YES }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OracleMismatch { event_id: 1 });
+        app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: OUTCOME_YES }, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Proposed);
+
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotSettled { event_id: 1 });
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ChallengePeriodActive { event_id: 1 });
+
+        app.update_block(|block| block.time = block.time.plus_seconds(86_400));
+        let err = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ChallengePeriodEnded { event_id: 1 });
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id: 1 }, &[]).unwrap();
+
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + stake + stake);
+    }
+
+    #[test]
+    fn disputed_resolution_is_ruled_by_arbitrator_and_bond_follows_ruling() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        let arbitrator_addr = app.api().addr_make("arbitrator0001");
+        let bond = Uint128::new(5_000);
+
+        // Disputes are never free.
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { dispute_bond: Some(Uint128::zero()), ..UpdateConfigMsg::default() }), &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidDisputeBond {});
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { challenge_period_secs: Some(3_600), dispute_bond: Some(bond), arbitrator: Some(arbitrator_addr.to_string()), ..UpdateConfigMsg::default() }), &[]).unwrap();
+        for description in ["Overturned", "Upheld"] {
+            app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg(description, Some(&oracle_addr))), &[]).unwrap();
+        }
+        for event_id in [1, 2] {
+            app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id, winning_outcome: OUTCOME_YES }, &[]).unwrap();
+        }
+
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(200);
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventNotOpen { event_id: 1 });
+
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoFundsSent {});
+        for sent in [1_000, bond.u128() + 1] {
+            let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 1 }, &coins(sent, BETTING_DENOM)).unwrap_err();
+            assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::IncorrectDisputeBond { .. }));
+        }
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 1 }, &coins(bond.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 2 }, &coins(bond.u128(), BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::DisputeResolution { event_id: 1 }, &coins(bond.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoResolutionProposed { event_id: 1 });
+        assert_eq!(escrow_invariant(&app, &contract_addr).dispute_bonds, bond + bond);
+
+        // A disputed proposal cannot be finalized however long it waits.
+        app.update_block(|block| block.time = block.time.plus_seconds(3_600));
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoResolutionProposed { event_id: 1 });
+        let err = app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::RuleOnDispute { event_id: 1, winning_outcome: Some(OUTCOME_NO) }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+
+        let user3_bal_before = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(arbitrator_addr.clone(), contract_addr.clone(), &ExecuteMsg::RuleOnDispute { event_id: 1, winning_outcome: Some(OUTCOME_NO) }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount, user3_bal_before + bond);
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Resolved);
+        assert_eq!(event_res.event.winning_outcome, Some(OUTCOME_NO));
+        assert_eq!(escrow_invariant(&app, &contract_addr).dispute_bonds, bond);
+
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::RuleOnDispute { event_id: 2, winning_outcome: Some(OUTCOME_YES) }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before);
+        let fees: FeesResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetFees {}).unwrap();
+        assert_eq!(fees.accrued_fees.amount, bond);
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.dispute_bonds, invariant.accrued_fees, invariant.balance.amount), (Uint128::zero(), bond, bond));
+    }
+
+    #[test]
+    fn oracle_quorum_proposes_on_agreement_and_disputes_on_conflict() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let oracle_addrs: Vec<Addr> = ["oracle0001", "oracle0002", "oracle0003"].iter().map(|id| app.api().addr_make(id)).collect();
+        let panel: Vec<String> = oracle_addrs.iter().map(|addr| addr.to_string()).collect();
+        let runners = vec!["Home".to_string(), "Draw".to_string(), "Away".to_string()];
+
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { oracles: Some(panel.clone()), oracle_threshold: Some(4), ..create_event_msg("Bad Quorum", None) }), &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOracleSet { .. }));
+        for description in ["Agreement", "Conflict"] {
+            app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { oracles: Some(panel.clone()), outcomes: Some(runners.clone()), ..create_event_msg(description, None) }), &[]).unwrap();
+        }
+
+        // No single panel member may void or suspend a quorum event.
+        for msg in [ExecuteMsg::CancelEvent { event_id: 1 }, ExecuteMsg::SuspendEvent { event_id: 1 }] {
+            let err = app.execute_contract(oracle_addrs[0].clone(), contract_addr.clone(), &msg, &[]).unwrap_err();
+            assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        }
+
+        // 2-of-3: the first vote closes betting, the second matching vote proposes the outcome.
+        app.execute_contract(oracle_addrs[0].clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: 0 }, &[]).unwrap();
+        let err = app.execute_contract(oracle_addrs[0].clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: 0 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::AlreadyVoted { event_id: 1 });
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: 0, stake: Uint128::new(1_000), odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(1_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::BettingClosed { event_id: 1 });
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Open);
+        app.execute_contract(oracle_addrs[2].clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: 0 }, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Proposed);
+        assert_eq!(event_res.event.proposal.unwrap().outcome, 0);
+
+        // Three different answers leave no outcome able to reach two votes.
+        for (oracle_addr, outcome) in oracle_addrs.iter().zip([0, 1, 2]) {
+            app.execute_contract(oracle_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 2, winning_outcome: outcome }, &[]).unwrap();
+        }
+        let votes: OracleVotesResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOracleVotes { event_id: 2 }).unwrap();
+        assert_eq!(votes.oracles, oracle_addrs);
+        assert_eq!(votes.threshold, 2);
+        assert_eq!(votes.votes.iter().map(|vote| (vote.oracle.clone(), vote.outcome)).collect::<Vec<_>>(), oracle_addrs.iter().cloned().zip([0, 1, 2]).collect::<Vec<_>>());
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 2 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Disputed);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::RuleOnDispute { event_id: 2, winning_outcome: None }, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 2 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Cancelled);
+    }
+
+    #[test]
+    fn resolve_from_oracle_contract_is_permissionless() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let resolver_code_id = app.store_code(Box::new(ContractWrapper::new(mock_resolver::execute, mock_resolver::instantiate, mock_resolver::query)));
+        let resolver_addr = app.instantiate_contract(resolver_code_id, admin_addr.clone(), &Empty {}, &[], "Mock Resolver", None).unwrap();
+
+        // A resolver market needs its oracle to be a contract.
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { resolver_market_id: Some("match-42".to_string()), ..create_event_msg("Human Resolver", Some(&user3_addr)) }), &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOracleSet { .. }));
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(CreateEventMsg { resolver_market_id: Some("match-42".to_string()), ..create_event_msg("Contract Resolver", Some(&resolver_addr)) }), &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Human Oracle", None)), &[]).unwrap();
+
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(200);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 2 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotResolverEvent { event_id: 2 });
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OracleOutcomeUnavailable { event_id: 1 });
+
+        app.execute_contract(admin_addr.clone(), resolver_addr.clone(), &mock_resolver::ExecuteMsg::SetOutcome { market_id: "match-42".to_string(), winning_outcome: OUTCOME_NO }, &[]).unwrap();
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 1 }, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.status, EventStatus::Proposed);
+        assert_eq!(event_res.event.proposal.unwrap().outcome, OUTCOME_NO);
+
+        app.update_block(|block| block.time = block.time.plus_seconds(86_400));
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id: 1 }, &[]).unwrap();
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before + stake + stake);
+    }
+
+    #[test]
+    fn price_threshold_market_resolves_from_price_feed() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let feed_code_id = app.store_code(Box::new(ContractWrapper::new(mock_price_feed::execute, mock_price_feed::instantiate, mock_price_feed::query)));
+        let feed_addr = app.instantiate_contract(feed_code_id, admin_addr.clone(), &Empty {}, &[], "Mock Price Feed", None).unwrap();
+        let observation_time = app.block_info().time.plus_seconds(3_600);
+        let condition = PriceCondition { price_feed_id: "INJ/USD".to_string(), comparator: PriceComparator::Above, threshold: Decimal::percent(2_500), observation_time };
+        let create_msg = ExecuteMsg::from(CreateEventMsg { price_condition: Some(condition.clone()), ..create_event_msg("INJ above $25 in an hour?", None) });
+
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &create_msg, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoPriceOracle {});
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { price_oracle: Some(feed_addr.to_string()), ..UpdateConfigMsg::default() }), &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &create_msg, &[]).unwrap();
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.betting_closes_at, Some(observation_time));
+
+        let stake = Uint128::new(10_000);
+        let odds = Decimal::percent(200);
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None }, &coins(stake.u128(), BETTING_DENOM)).unwrap();
+
+        // Nobody can type the answer in, and the feed is only read at the observation time.
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::ProposeResolution { event_id: 1, winning_outcome: OUTCOME_YES }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::PriceMarket { event_id: 1 });
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::ObservationTimeNotReached { event_id: 1 });
+
+        app.update_block(|block| block.time = observation_time);
+        let err = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OracleOutcomeUnavailable { event_id: 1 });
+        app.execute_contract(admin_addr.clone(), feed_addr.clone(), &mock_price_feed::ExecuteMsg::SetPrice { feed_id: "INJ/USD".to_string(), observation_time, price: Decimal::percent(2_500) }, &[]).unwrap();
+        let res = app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::ResolveFromOracle { event_id: 1 }, &[]).unwrap();
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "observed_price" && attr.value == "25"));
+
+        // Exactly at the threshold is not above it.
+        let event_res: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 1 }).unwrap();
+        assert_eq!(event_res.event.proposal.unwrap().outcome, OUTCOME_NO);
+        app.update_block(|block| block.time = block.time.plus_seconds(86_400));
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::FinalizeResolution { event_id: 1 }, &[]).unwrap();
+        let user2_bal_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_bal_before + stake + stake);
+    }
+
+    #[test]
+    fn operators_trade_from_owner_deposit_within_scope_and_expiry() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let owner_addr = app.api().addr_make(USER1_ID_STR);
+        let stranger_addr = app.api().addr_make(USER2_ID_STR);
+        let bot_addr = app.api().addr_make(USER3_ID_STR);
+        let expires = app.block_info().time.plus_seconds(1_000);
+
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Market Making", None)), &[]).unwrap();
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &ExecuteMsg::Deposit {}, &coins(30_000, BETTING_DENOM)).unwrap();
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &ExecuteMsg::GrantOperator { operator: bot_addr.to_string(), scope: OperatorScope::Place, expires: Some(expires) }, &[]).unwrap();
+
+        let stake = Uint128::new(10_000);
+        let place_for_owner = ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake, odds: Decimal::percent(200), time_in_force: None, owner: Some(owner_addr.to_string()) };
+        let err = app.execute_contract(stranger_addr.clone(), contract_addr.clone(), &place_for_owner, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        let err = app.execute_contract(bot_addr.clone(), contract_addr.clone(), &place_for_owner, &coins(stake.u128(), BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OperatorFundsNotAccepted {});
+        app.execute_contract(bot_addr.clone(), contract_addr.clone(), &place_for_owner, &[]).unwrap();
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!(order.order.owner, owner_addr);
+        let deposit: DepositResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetDeposit { addr: owner_addr.to_string() }).unwrap();
+        assert_eq!(deposit.amount.amount, Uint128::new(20_000));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_deposits, invariant.total_escrow), (Uint128::new(20_000), stake));
+
+        // A place-only operator cannot cancel; widening the grant lets it, and the refund goes to the owner.
+        let err = app.execute_contract(bot_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &ExecuteMsg::GrantOperator { operator: bot_addr.to_string(), scope: OperatorScope::Both, expires: Some(expires) }, &[]).unwrap();
+        let owner_bal_before = app.wrap().query_balance(owner_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let bot_bal_before = app.wrap().query_balance(bot_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(bot_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(owner_addr.to_string(), BETTING_DENOM).unwrap().amount, owner_bal_before + stake);
+        assert_eq!(app.wrap().query_balance(bot_addr.to_string(), BETTING_DENOM).unwrap().amount, bot_bal_before);
+
+        let err = app.execute_contract(bot_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(25_000), odds: Decimal::percent(200), time_in_force: None, owner: Some(owner_addr.to_string()) }, &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InsufficientDeposit { .. }));
+
+        app.update_block(|block| block.time = expires);
+        let err = app.execute_contract(bot_addr.clone(), contract_addr.clone(), &place_for_owner, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Unauthorized {});
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &ExecuteMsg::RevokeOperator { operator: bot_addr.to_string() }, &[]).unwrap();
+        let grant: OperatorResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOperator { owner: owner_addr.to_string(), operator: bot_addr.to_string() }).unwrap();
+        assert_eq!(grant.grant, None);
+
+        let owner_bal_before = app.wrap().query_balance(owner_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &ExecuteMsg::WithdrawDeposit { amount: Uint128::new(20_000) }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(owner_addr.to_string(), BETTING_DENOM).unwrap().amount, owner_bal_before + Uint128::new(20_000));
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+        assert!(escrow_invariant(&app, &contract_addr).total_deposits.is_zero());
+    }
+
+    #[test]
+    fn batch_orders_take_one_deposit_and_refund_in_one_transfer() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        for description in ["Match A", "Match B"] {
+            app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg(description, None)), &[]).unwrap();
+        }
+
+        // A 10k back plus a 10k lay at 3.0 (20k liability) on each event: 30k per event.
+        let batch: Vec<NewOrder> = [1u64, 2].iter().flat_map(|&event_id| [
+            NewOrder { event_id, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(10_000), odds: Decimal::percent(200), time_in_force: None },
+            NewOrder { event_id, order_type: OrderType::Lay, outcome: OUTCOME_NO, stake: Uint128::new(10_000), odds: Decimal::percent(300), time_in_force: None },
+        ]).collect();
+        let place = ExecuteMsg::PlaceOrders { orders: batch, owner: None };
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(50_000, BETTING_DENOM)).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InsufficientFundsSent { .. }));
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &place, &coins(60_000, BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrders { orders: vec![], owner: None }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidBatchSize { size: 0, max: 50 });
+
+        let bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrders { order_ids: vec![1, 2] }, &[]).unwrap();
+        assert_eq!(res.events.iter().filter(|e| e.ty == "transfer").count(), 1);
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before + Uint128::new(30_000));
+
+        // Cancelling everything skips the orders already cancelled and refunds the rest at once.
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelAllOrders { event_id: None, owner: None }, &[]).unwrap();
+        assert_eq!(res.events.iter().filter(|e| e.ty == "transfer").count(), 1);
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "more_remaining" && attr.value == "false"));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Cancelled);
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before + Uint128::new(60_000));
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+    }
+
+    #[test]
+    fn amend_order_settles_escrow_delta_and_keeps_priority_only_when_shrinking() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Amendments", None)), &[]).unwrap();
+        for user in [&user1_addr, &user2_addr] {
+            app.execute_contract(user.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(10_000), odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(10_000, BETTING_DENOM)).unwrap();
+        }
+
+        // Shrinking at the same odds refunds the difference and keeps order 1 first in the queue.
+        let bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::AmendOrder { order_id: 1, new_odds: None, new_remaining_stake: Some(Uint128::new(6_000)) }, &[]).unwrap();
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "priority_kept" && attr.value == "true"));
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before + Uint128::new(4_000));
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::AmendOrder { order_id: 1, new_odds: Some(Decimal::percent(200)), new_remaining_stake: None }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToAmend { order_id: 1 });
+
+        // Growing an order needs exactly the extra escrow and replaces it with a new order.
+        let grow = ExecuteMsg::AmendOrder { order_id: 2, new_odds: None, new_remaining_stake: Some(Uint128::new(12_000)) };
+        let err = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &grow, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoFundsSent {});
+        let res = app.execute_contract(user2_addr.clone(), contract_addr.clone(), &grow, &coins(2_000, BETTING_DENOM)).unwrap();
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "amended_order_id" && attr.value == "3"));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 2 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Cancelled);
+
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(6_000), odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(6_000, BETTING_DENOM)).unwrap();
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Filled);
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 3 }).unwrap();
+        assert_eq!((order.order.status, order.order.remaining_backer_stake.amount), (OrderStatus::Open, Uint128::new(12_000)));
+        assert_eq!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount, Uint128::new(24_000));
+    }
+
+    #[test]
+    fn time_in_force_controls_how_long_orders_rest() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Time In Force", None)), &[]).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(5_000), odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(5_000, BETTING_DENOM)).unwrap();
+
+        let back = |stake: u128, time_in_force: TimeInForce| ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(200), time_in_force: Some(time_in_force), owner: None };
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(10_000, TimeInForce::FillOrKill), &coins(10_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::FillOrKillNotFilled {});
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(1_000, TimeInForce::PostOnly), &coins(1_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::PostOnlyWouldMatch {});
+
+        // Immediate-or-cancel fills the 5k available and hands back the rest at once.
+        let bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(10_000, TimeInForce::ImmediateOrCancel), &coins(10_000, BETTING_DENOM)).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before - Uint128::new(5_000));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 2 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Cancelled);
+
+        // A good-til-date order stops matching at its expiry and can then be cleaned up by anyone.
+        let expires = app.block_info().time.plus_seconds(100);
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(3_000), odds: Decimal::percent(300), time_in_force: Some(TimeInForce::GoodTilDate { expires }), owner: None }, &coins(3_000, BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelExpiredOrders { order_ids: vec![2, 3] }, &[]).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OrderNotExpired { order_id: 3 });
+        app.update_block(|block| block.time = expires);
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(3_000), odds: Decimal::percent(300), time_in_force: None, owner: None }, &coins(6_000, BETTING_DENOM)).unwrap();
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Open);
+
+        let bal_before = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        // Order 4 never expires, but it does not stop order 3 from being cleaned up.
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelExpiredOrders { order_ids: vec![4, 3] }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before + Uint128::new(3_000));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Open);
+    }
+
+    #[test]
+    fn market_order_sweeps_price_levels_within_worst_odds() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Market Orders", None)), &[]).unwrap();
+        for (stake, odds_percent) in [(3_000u128, 250u64), (4_000, 220), (5_000, 190)] {
+            let liability = stake * (odds_percent as u128 - 100) / 100;
+            app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(odds_percent), time_in_force: None, owner: None }, &coins(liability, BETTING_DENOM)).unwrap();
+        }
+
+        let market_back = |worst_odds: Decimal| ExecuteMsg::PlaceMarketOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(10_000), worst_odds, owner: None };
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &market_back(Decimal::percent(300)), &coins(10_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoLiquidityWithinOdds { event_id: 1 });
+
+        // Takes 3k at 2.5 and 4k at 2.2, stops before 1.9 and refunds the unfilled 3k.
+        let bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let res = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &market_back(Decimal::percent(200)), &coins(10_000, BETTING_DENOM)).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, bal_before - Uint128::new(7_000));
+        assert_eq!(res.custom_attrs(1).iter().filter(|attr| attr.key == "matched_bet_id").count(), 2);
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "matched_stake" && attr.value == "7000"));
+        assert!(res.custom_attrs(1).iter().any(|attr| attr.key == "average_odds" && attr.value == "2.328571428571428571"));
+        let order: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 3 }).unwrap();
+        assert_eq!(order.order.status, OrderStatus::Open);
+    }
+
+    #[test]
+    fn order_book_depth_and_per_user_queries() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Depth", None)), &[]).unwrap();
+        for (stake, odds_percent) in [(2_000u128, 250u64), (1_000, 250), (3_000, 220)] {
+            let liability = stake * (odds_percent as u128 - 100) / 100;
+            app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(odds_percent), time_in_force: None, owner: None }, &coins(liability, BETTING_DENOM)).unwrap();
+        }
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(4_000), odds: Decimal::percent(300), time_in_force: None, owner: None }, &coins(4_000, BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(2_000), odds: Decimal::percent(250), time_in_force: None, owner: None }, &coins(2_000, BETTING_DENOM)).unwrap();
+
+        let book: OrderBookResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrderBook { event_id: 1, outcome: OUTCOME_YES, depth: None }).unwrap();
+        assert_eq!(book.available_to_back, vec![
+            PriceLevel { odds: Decimal::percent(250), stake: Uint128::new(1_000), order_count: 1 },
+            PriceLevel { odds: Decimal::percent(220), stake: Uint128::new(3_000), order_count: 1 },
+        ]);
+        assert_eq!(book.available_to_lay, vec![PriceLevel { odds: Decimal::percent(300), stake: Uint128::new(4_000), order_count: 1 }]);
+        assert_eq!((book.last_traded_odds, book.matched_volume), (Some(Decimal::percent(250)), Uint128::new(2_000)));
+        let book: OrderBookResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrderBook { event_id: 1, outcome: OUTCOME_YES, depth: Some(1) }).unwrap();
+        assert_eq!(book.available_to_back.len(), 1);
+
+        let filled: OrdersResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListOrdersByOwner { owner: user2_addr.to_string(), status_filter: Some(OrderStatus::Filled), start_after: None, limit: None }).unwrap();
+        assert_eq!(filled.orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![1]);
+        let all: OrdersResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListOrdersByOwner { owner: user2_addr.to_string(), status_filter: None, start_after: Some(1), limit: None }).unwrap();
+        assert_eq!(all.orders.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        let bets_for = |addr: &Addr, role: BetRole| -> usize {
+            let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListBetsByUser { addr: addr.to_string(), role, start_after: None, limit: None }).unwrap();
+            bets.matched_bets.len()
+        };
+        assert_eq!((bets_for(&user2_addr, BetRole::Layer), bets_for(&user2_addr, BetRole::Backer), bets_for(&user1_addr, BetRole::Any)), (1, 0, 1));
+
+        // 2k backed at 2.5 against user2's lay: user1 wins 3k on Yes, user2 wins 2k on No.
+        let position: UserPositionResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetUserPosition { addr: user1_addr.to_string(), event_id: 1 }).unwrap();
+        assert_eq!((position.total_staked, position.profit_by_outcome), (Uint128::new(2_000), vec![Int128::new(3_000), Int128::new(-2_000)]));
+        let position: UserPositionResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetUserPosition { addr: user2_addr.to_string(), event_id: 1 }).unwrap();
+        assert_eq!((position.total_liability, position.open_escrow), (Uint128::new(3_000), Uint128::new(5_100)));
+        assert_eq!(position.profit_by_outcome, vec![Int128::new(-3_000), Int128::new(2_000)]);
+    }
+
+    #[test]
+    fn matched_bets_are_listed_by_event_backer_and_layer() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        for description in ["First", "Second"] {
+            app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg(description, None)), &[]).unwrap();
+        }
+
+        // Bets 1-4: user1 backs against user2 on both events and against user3 on event 1, then
+        // lays to user2 on event 1.
+        for (event_id, backer, layer) in [(1, &user1_addr, &user2_addr), (2, &user1_addr, &user2_addr), (1, &user1_addr, &user3_addr), (1, &user2_addr, &user1_addr)] {
+            for (addr, order_type) in [(layer, OrderType::Lay), (backer, OrderType::Back)] {
+                app.execute_contract(addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id, order_type, outcome: OUTCOME_YES, stake: Uint128::new(1_000), odds: Decimal::percent(200), time_in_force: None, owner: None }, &coins(1_000, BETTING_DENOM)).unwrap();
+            }
+        }
+
+        let by_event = |event_id: u64, start_after: Option<u64>, limit: Option<u32>| -> Vec<u64> {
+            let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id, start_after, limit }).unwrap();
+            bets.matched_bets.iter().map(|bet| bet.id).collect()
+        };
+        assert_eq!(by_event(1, None, None), vec![1, 3, 4]);
+        assert_eq!(by_event(1, Some(1), Some(1)), vec![3]);
+        assert_eq!(by_event(1, Some(4), None), Vec::<u64>::new());
+        assert_eq!(by_event(2, None, None), vec![2]);
+
+        let by_user = |addr: &Addr, role: BetRole, start_after: Option<u64>, limit: Option<u32>| -> Vec<u64> {
+            let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListBetsByUser { addr: addr.to_string(), role, start_after, limit }).unwrap();
+            bets.matched_bets.iter().map(|bet| bet.id).collect()
+        };
+        assert_eq!(by_user(&user1_addr, BetRole::Backer, None, None), vec![1, 2, 3]);
+        assert_eq!(by_user(&user1_addr, BetRole::Backer, Some(1), Some(1)), vec![2]);
+        assert_eq!(by_user(&user1_addr, BetRole::Layer, None, None), vec![4]);
+        assert_eq!(by_user(&user1_addr, BetRole::Any, Some(2), Some(2)), vec![3, 4]);
+        assert_eq!(by_user(&user2_addr, BetRole::Layer, None, None), vec![1, 2]);
+        assert_eq!(by_user(&user2_addr, BetRole::Backer, None, None), vec![4]);
+        assert_eq!(by_user(&user3_addr, BetRole::Layer, Some(3), None), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn migrates_v0_1_store_in_place_and_refuses_foreign_or_newer_versions() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        let now = app.block_info().time;
+        let coin = |amount: u128| Coin::new(amount, BETTING_DENOM);
+
+        // v0.1.0 state: on open event 1, user1 backed Yes 10k at 2.0 and 6k of it was laid by
+        // user2; user3 lays No 5k at 3.0. Event 2 was resolved, which paid everything out.
+        {
+            let mut storage = app.contract_storage_mut(&contract_addr);
+            let storage = storage.as_mut();
+            cw2::set_contract_version(storage, "crates.io:injective-betting", "0.1.0").unwrap();
+            v0_1::CONFIG.save(storage, &v0_1::Config { admin: admin_addr.clone(), betting_denom: BETTING_DENOM.to_string() }).unwrap();
+            for (id, status, winning_outcome) in [(1u64, EventStatus::Open, None), (2, EventStatus::Resolved, Some(v0_1::Outcome::No))] {
+                v0_1::EVENTS.save(storage, id, &v0_1::Event { id, creator: admin_addr.clone(), description: format!("Legacy {}", id), oracle: oracle_addr.clone(), status, winning_outcome, resolution_deadline: None, creation_time: now }).unwrap();
+            }
+            let legacy_orders = [
+                (1u64, &user1_addr, OrderType::Back, v0_1::Outcome::Yes, 10_000u128, 4_000u128, 200u64, OrderStatus::PartiallyFilled),
+                (2, &user2_addr, OrderType::Lay, v0_1::Outcome::Yes, 6_000, 0, 200, OrderStatus::Filled),
+                (3, &user3_addr, OrderType::Lay, v0_1::Outcome::No, 5_000, 5_000, 300, OrderStatus::Open),
+            ];
+            for (id, owner, order_type, outcome, initial, remaining, odds_percent, status) in legacy_orders {
+                let order = v0_1::Order { id, event_id: 1, owner: owner.clone(), order_type, outcome, initial_backer_stake: coin(initial), remaining_backer_stake: coin(remaining), odds: Decimal::percent(odds_percent), creation_time: now, status };
+                v0_1::orders().save(storage, id, &order).unwrap();
+            }
+            for (id, event_id) in [(1u64, 1u64), (2, 2)] {
+                v0_1::MATCHED_BETS.save(storage, id, &v0_1::MatchedBet { id, event_id, backer_addr: user1_addr.clone(), lay_addr: user2_addr.clone(), backer_stake: coin(6_000), layer_liability: coin(6_000), outcome_backed: v0_1::Outcome::Yes, odds: Decimal::percent(200), creation_time: now }).unwrap();
+                EVENT_TO_MATCHED_BETS.save(storage, event_id, &vec![id]).unwrap();
+            }
+        }
+        // Escrow still held for event 1: user1's 4k unmatched back, the 6k + 6k pot and user3's 10k liability.
+        app.send_tokens(user1_addr.clone(), contract_addr.clone(), &coins(26_000, BETTING_DENOM)).unwrap();
+
+        let code_id = app.wrap().query_wasm_contract_info(contract_addr.clone()).unwrap().code_id;
+        app.migrate_contract(admin_addr.clone(), contract_addr.clone(), &MigrateMsg {}, code_id).unwrap();
+        {
+            let storage = app.contract_storage(&contract_addr);
+            assert!(EVENT_TO_MATCHED_BETS.may_load(storage.as_ref(), 1).unwrap().is_none());
+            let legacy_index = b"orders__event_outcome_params";
+            assert!(!storage.range(None, None, CwOrder::Ascending).any(|(key, _)| key.windows(legacy_index.len()).any(|part| part == legacy_index)));
+        }
+        let config: ConfigResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {}).unwrap();
+        assert_eq!((config.commission_bps, config.fee_collector), (0, admin_addr.clone()));
+        let event: EventResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetEvent { event_id: 2 }).unwrap();
+        assert_eq!((event.event.status, event.event.winning_outcome), (EventStatus::Settled, Some(OUTCOME_NO)));
+
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.iter().map(|bet| (bet.id, bet.outcome_backed)).collect::<Vec<_>>(), vec![(1, OUTCOME_YES)]);
+        let book: OrderBookResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrderBook { event_id: 1, outcome: OUTCOME_YES, depth: None }).unwrap();
+        assert_eq!(book.available_to_lay, vec![PriceLevel { odds: Decimal::percent(200), stake: Uint128::new(4_000), order_count: 1 }]);
+        assert_eq!(book.matched_volume, Uint128::new(6_000));
+        for (owner, order_ids) in [(&user1_addr, vec![1]), (&user2_addr, vec![2]), (&user3_addr, vec![3])] {
+            let owned: OrdersResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListOrdersByOwner { owner: owner.to_string(), status_filter: None, start_after: None, limit: None }).unwrap();
+            assert_eq!(owned.orders.iter().map(|order| order.id).collect::<Vec<_>>(), order_ids);
+        }
+        for (addr, role) in [(&user1_addr, BetRole::Backer), (&user2_addr, BetRole::Layer)] {
+            let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListBetsByUser { addr: addr.to_string(), role, start_after: None, limit: None }).unwrap();
+            assert_eq!(bets.matched_bets.iter().map(|bet| bet.id).collect::<Vec<_>>(), vec![1, 2]);
+        }
+
+        // Migrated orders and positions behave like native ones.
+        let user3_bal_before = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 3 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount, user3_bal_before + Uint128::new(10_000));
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        let user1_bal_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_bal_before + Uint128::new(16_000));
+        assert!(app.wrap().query_balance(contract_addr.as_str(), BETTING_DENOM).unwrap().amount.is_zero());
+
+        // Re-running at the current version is a no-op; newer or foreign contracts are refused.
+        app.migrate_contract(admin_addr.clone(), contract_addr.clone(), &MigrateMsg {}, code_id).unwrap();
+        for (name, version) in [("crates.io:injective-betting", "9.0.0"), ("crates.io:other-contract", "0.1.0")] {
+            cw2::set_contract_version(app.contract_storage_mut(&contract_addr).as_mut(), name, version).unwrap();
+            let err = app.migrate_contract(admin_addr.clone(), contract_addr.clone(), &MigrateMsg {}, code_id).unwrap_err();
+            assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::MigrationError { .. }));
+        }
+    }
+
+    #[test]
+    fn risk_limits_bound_stakes_exposure_and_event_volume() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let limits = RiskLimits { min_stake: Some(Uint128::new(1_000)), max_stake: Some(Uint128::new(50_000)), max_liability_per_user: Some(Uint128::new(20_000)), max_matched_volume: Some(Uint128::new(15_000)) };
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(UpdateConfigMsg { limits: Some(limits), ..UpdateConfigMsg::default() }), &[]).unwrap();
+
+        // Event creators other than the admin may only tighten the global limits.
+        let create = |limits: RiskLimits| ExecuteMsg::from(CreateEventMsg { limits: Some(limits), ..create_event_msg("Limits", None) });
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &create(RiskLimits { max_stake: Some(Uint128::new(100_000)), ..RiskLimits::default() }), &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidLimits { .. }));
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &create(RiskLimits::default()), &[]).unwrap();
+
+        let order = |order_type: OrderType, stake: u128| ExecuteMsg::PlaceOrder { event_id: 1, order_type, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(200), time_in_force: None, owner: None };
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, 500), &coins(500, BETTING_DENOM)).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::StakeBelowMinimum { .. }));
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, 60_000), &coins(60_000, BETTING_DENOM)).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::StakeAboveMaximum { .. }));
+
+        // 15k already at risk, so another 6k would expose user1 to 21k.
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, 15_000), &coins(15_000, BETTING_DENOM)).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, 6_000), &coins(6_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::LiabilityLimitExceeded { event_id: 1, exposure: "21000".to_string(), limit: "20000".to_string() });
+
+        // Matching stops at the 15k volume cap; the rest of the lay rests and the event takes no new orders.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &order(OrderType::Lay, 20_000), &coins(20_000, BETTING_DENOM)).unwrap();
+        let lay: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 2 }).unwrap();
+        assert_eq!((lay.order.status, lay.order.remaining_backer_stake.amount), (OrderStatus::PartiallyFilled, Uint128::new(5_000)));
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, 1_000), &coins(1_000, BETTING_DENOM)).unwrap_err();
+        assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::EventVolumeLimitReached { event_id: 1 });
+    }
+
+    #[test]
+    fn orders_must_be_priced_on_the_odds_ladder() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Ladder", None)), &[]).unwrap();
+
+        let ladder: OddsLadderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOddsLadder {}).unwrap();
+        assert_eq!((ladder.min_odds, ladder.max_odds), (Decimal::percent(101), Decimal::percent(100_000)));
+        assert_eq!(ladder.bands[1], OddsBand { up_to: Decimal::percent(300), tick: Decimal::percent(2) });
+
+        let back = |odds: Decimal| ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(1_000), odds, time_in_force: None, owner: None };
+        for odds in [Decimal::percent(101), Decimal::percent(202), Decimal::percent(345), Decimal::percent(100_000)] {
+            app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(odds), &coins(1_000, BETTING_DENOM)).unwrap();
+        }
+        let just_above_two = Decimal::percent(200) + Decimal::raw(1);
+        for odds in [just_above_two, Decimal::percent(201), Decimal::percent(343), Decimal::percent(101_000)] {
+            let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(odds), &coins(1_000, BETTING_DENOM)).unwrap_err();
+            assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::OddsOffLadder { odds: odds.to_string() });
+        }
+
+        // A band must be a whole number of its ticks; a valid coarser ladder replaces the default one.
+        let update = |odds_ladder: Vec<OddsBand>| ExecuteMsg::from(UpdateConfigMsg { odds_ladder: Some(odds_ladder), ..UpdateConfigMsg::default() });
+        let err = app.execute_contract(admin_addr.clone(), contract_addr.clone(), &update(vec![OddsBand { up_to: Decimal::percent(200), tick: Decimal::percent(3) }]), &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidOddsLadder { .. }));
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &update(vec![OddsBand { up_to: Decimal::percent(200), tick: Decimal::percent(10) }, OddsBand { up_to: Decimal::percent(500), tick: Decimal::percent(50) }]), &[]).unwrap();
+        let err = app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(Decimal::percent(101)), &coins(1_000, BETTING_DENOM)).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::OddsOffLadder { .. }));
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &back(Decimal::percent(450)), &coins(1_000, BETTING_DENOM)).unwrap();
+    }
+
+    #[test]
+    fn lay_escrow_fills_and_refund_add_up_to_the_deposit() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Rounding", Some(&oracle_addr))), &[]).unwrap();
+        let odds = Decimal::percent(133);
+
+        // Liability on 10 at 1.33 is 3.3, truncated to 3. Truncating each 1-unit fill on its own would
+        // charge nothing and strand the deposit.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(10), odds, time_in_force: None, owner: None }, &coins(3, BETTING_DENOM)).unwrap();
+        for _ in 0..4 {
+            app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(1), odds, time_in_force: None, owner: None }, &coins(1, BETTING_DENOM)).unwrap();
+        }
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        let liabilities: Vec<u128> = bets.matched_bets.iter().map(|bet| bet.layer_liability.amount.u128()).collect();
+        assert_eq!(liabilities, vec![1, 0, 0, 1]);
+        let lay: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!(lay.order.remaining_escrow, Uint128::new(1));
+        assert_eq!(escrow_invariant(&app, &contract_addr).total_escrow, Uint128::new(7));
+
+        let user2_before = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelOrder { order_id: 1 }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_before + Uint128::new(1));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(6), Uint128::new(6)));
+
+        // Paying out the matched bets leaves no dust behind.
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::ClaimWinnings { event_id: 1 }, &[]).unwrap();
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::zero(), Uint128::zero()));
+    }
+
+    #[test]
+    fn lay_price_improvement_and_amendment_refund_exact_escrow() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Rounding", None)), &[]).unwrap();
+        let user2_start = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+
+        // A lay of 3 at 1.99 escrows 2 (2.97 truncated) but fills against a back at 1.5 for a liability of 1.
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(3), odds: Decimal::percent(150), time_in_force: None, owner: None }, &coins(3, BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(3), odds: Decimal::percent(199), time_in_force: None, owner: None }, &coins(2, BETTING_DENOM)).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_start - Uint128::new(1));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(4), Uint128::new(4)));
+
+        // Shrinking a lay of 10 at 1.33 from 3 escrowed to 7 (2.31) refunds exactly 1.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(10), odds: Decimal::percent(133), time_in_force: None, owner: None }, &coins(3, BETTING_DENOM)).unwrap();
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::AmendOrder { order_id: 3, new_odds: None, new_remaining_stake: Some(Uint128::new(7)) }, &[]).unwrap();
+        let lay: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 3 }).unwrap();
+        assert_eq!(lay.order.remaining_escrow, Uint128::new(2));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(6), Uint128::new(6)));
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_start - Uint128::new(3));
+    }
+
+    #[test]
+    fn lay_escrow_is_exact_on_whole_liabilities_and_large_stakes() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        let whale_backer_addr = app.api().addr_make("whale_backer");
+        let whale_layer_addr = app.api().addr_make("whale_layer");
+        let large_stake = Uint128::new(100_000_000_000_000_000_000);
+        app.init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, &whale_backer_addr, coins(large_stake.u128(), BETTING_DENOM)).unwrap();
+            router.bank.init_balance(storage, &whale_layer_addr, coins(large_stake.u128() * 3, BETTING_DENOM)).unwrap();
+        });
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Rounding", Some(&oracle_addr))), &[]).unwrap();
+        let order = |order_type: OrderType, stake: Uint128, odds: Decimal| ExecuteMsg::PlaceOrder { event_id: 1, order_type, outcome: OUTCOME_YES, stake, odds, time_in_force: None, owner: None };
+
+        // At 1.5 a lay of 10 owes exactly 5, and fills of 4 and 6 owe exactly 2 and 3.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &order(OrderType::Lay, Uint128::new(10), Decimal::percent(150)), &coins(5, BETTING_DENOM)).unwrap();
+        for stake in [4, 6] {
+            app.execute_contract(user1_addr.clone(), contract_addr.clone(), &order(OrderType::Back, Uint128::new(stake), Decimal::percent(150)), &coins(stake, BETTING_DENOM)).unwrap();
+        }
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.iter().map(|bet| bet.layer_liability.amount.u128()).collect::<Vec<_>>(), vec![2, 3]);
+        let lay: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 1 }).unwrap();
+        assert_eq!((lay.order.status, lay.order.remaining_escrow), (OrderStatus::Filled, Uint128::zero()));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(15), Uint128::new(15)));
+
+        // 10^20 at 4.0 is still within range: the liability is 3 * 10^20.
+        app.execute_contract(whale_backer_addr.clone(), contract_addr.clone(), &order(OrderType::Back, large_stake, Decimal::percent(400)), &coins(large_stake.u128(), BETTING_DENOM)).unwrap();
+        app.execute_contract(whale_layer_addr.clone(), contract_addr.clone(), &order(OrderType::Lay, large_stake, Decimal::percent(400)), &coins(large_stake.u128() * 3, BETTING_DENOM)).unwrap();
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: Some(2), limit: None }).unwrap();
+        assert_eq!(bets.matched_bets[0].layer_liability.amount, large_stake * Uint128::new(3));
+        assert_eq!(escrow_invariant(&app, &contract_addr).total_escrow, large_stake * Uint128::new(4) + Uint128::new(15));
+
+        // Ten times that overflows the liability calculation and is refused rather than truncated.
+        let err = app.execute_contract(whale_layer_addr.clone(), contract_addr.clone(), &order(OrderType::Lay, large_stake * Uint128::new(10), Decimal::percent(150)), &[]).unwrap_err();
+        assert!(matches!(err.downcast::<ContractError>().unwrap(), ContractError::CalculationError { .. }));
+
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(whale_backer_addr.to_string(), BETTING_DENOM).unwrap().amount, large_stake * Uint128::new(4));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::zero(), Uint128::zero()));
+    }
+
+    #[test]
+    fn lay_sweeping_several_prices_refunds_improvement_and_remainder() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Rounding", Some(&oracle_addr))), &[]).unwrap();
+        for (stake, odds_percent) in [(3u128, 150u64), (4, 175), (5, 200)] {
+            app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(odds_percent), time_in_force: None, owner: None }, &coins(stake, BETTING_DENOM)).unwrap();
+        }
+        let user2_start = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+
+        // An IOC lay of 10 at 1.99 escrows 9 (9.9 truncated). It fills 3 at 1.5 for a liability of
+        // 1 (1.5 truncated), freeing 3, then 4 at 1.75 for exactly 3, freeing 4. The remaining 3
+        // still needs 2, which the IOC refunds with the 2 + 1 of price improvement.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(10), odds: Decimal::percent(199), time_in_force: Some(TimeInForce::ImmediateOrCancel), owner: None }, &coins(9, BETTING_DENOM)).unwrap();
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: None, limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.iter().map(|bet| (bet.backer_stake.amount.u128(), bet.layer_liability.amount.u128())).collect::<Vec<_>>(), vec![(3, 1), (4, 3)]);
+        let lay: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 4 }).unwrap();
+        assert_eq!((lay.order.status, lay.order.remaining_escrow), (OrderStatus::Cancelled, Uint128::zero()));
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_start - Uint128::new(4));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(16), Uint128::new(16)));
+
+        // A market lay of 10 down to 2.5 is funded at 15. Filling 5 at 2.0 owes 5 of the 8 it frees,
+        // and the unfilled 5 releases the other 7.
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceMarketOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(10), worst_odds: Decimal::percent(250), owner: None }, &coins(15, BETTING_DENOM)).unwrap();
+        let bets: MatchedBetsResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::ListMatchedBetsByEvent { event_id: 1, start_after: Some(2), limit: None }).unwrap();
+        assert_eq!(bets.matched_bets.iter().map(|bet| bet.layer_liability.amount.u128()).collect::<Vec<_>>(), vec![5]);
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_start - Uint128::new(9));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(21), Uint128::new(21)));
+
+        resolve_event(&mut app, &contract_addr, &oracle_addr, 1, OUTCOME_YES).unwrap();
+        let user1_before = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount, user1_before + Uint128::new(21));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::zero(), Uint128::zero()));
+    }
+
+    #[test]
+    fn cancel_all_and_void_refund_remaining_escrow() {
+        let mut app = default_app();
+        let (contract_addr, admin_addr) = setup_contract(&mut app, BETTING_DENOM.to_string());
+        let user1_addr = app.api().addr_make(USER1_ID_STR);
+        let user2_addr = app.api().addr_make(USER2_ID_STR);
+        let user3_addr = app.api().addr_make(USER3_ID_STR);
+        let oracle_addr = app.api().addr_make(ORACLE_ID_STR);
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::from(create_event_msg("Rounding", Some(&oracle_addr))), &[]).unwrap();
+        let lay = |stake: u128, odds_percent: u64| ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Lay, outcome: OUTCOME_YES, stake: Uint128::new(stake), odds: Decimal::percent(odds_percent), time_in_force: None, owner: None };
+        let user1_start = app.wrap().query_balance(user1_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user2_start = app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount;
+        let user3_start = app.wrap().query_balance(user3_addr.to_string(), BETTING_DENOM).unwrap().amount;
+
+        // Lays of 7 at 1.33, 10 at 1.67 and 9 at 1.99 escrow 2, 6 and 8. Backing 4 at 1.99 leaves the
+        // last one needing 4 for its remaining 5 (4.95 truncated).
+        for (stake, odds_percent, escrow) in [(7, 133, 2), (10, 167, 6), (9, 199, 8)] {
+            app.execute_contract(user2_addr.clone(), contract_addr.clone(), &lay(stake, odds_percent), &coins(escrow, BETTING_DENOM)).unwrap();
+        }
+        app.execute_contract(user3_addr.clone(), contract_addr.clone(), &lay(6, 133), &coins(1, BETTING_DENOM)).unwrap();
+        app.execute_contract(user1_addr.clone(), contract_addr.clone(), &ExecuteMsg::PlaceOrder { event_id: 1, order_type: OrderType::Back, outcome: OUTCOME_YES, stake: Uint128::new(4), odds: Decimal::percent(199), time_in_force: None, owner: None }, &coins(4, BETTING_DENOM)).unwrap();
+        let partly_filled: OrderResponse = app.wrap().query_wasm_smart(contract_addr.clone(), &QueryMsg::GetOrder { order_id: 3 }).unwrap();
+        assert_eq!(partly_filled.order.remaining_escrow, Uint128::new(4));
+
+        app.execute_contract(user2_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelAllOrders { event_id: Some(1), owner: None }, &[]).unwrap();
+        assert_eq!(app.wrap().query_balance(user2_addr.to_string(), BETTING_DENOM).unwrap().amount, user2_start - Uint128::new(4));
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::new(9), Uint128::new(9)));
+
+        // Voiding returns the matched stakes and user3's still-resting escrow.
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::CancelEvent { event_id: 1 }, &[]).unwrap();
+        app.execute_contract(admin_addr.clone(), contract_addr.clone(), &ExecuteMsg::SettleEvent { event_id: 1, limit: None }, &[]).unwrap();
+        for (addr, start) in [(&user1_addr, user1_start), (&user2_addr, user2_start), (&user3_addr, user3_start)] {
+            assert_eq!(app.wrap().query_balance(addr.to_string(), BETTING_DENOM).unwrap().amount, start);
+        }
+        let invariant = escrow_invariant(&app, &contract_addr);
+        assert_eq!((invariant.total_escrow, invariant.balance.amount), (Uint128::zero(), Uint128::zero()));
     }
-}
\ No newline at end of file
+}